@@ -0,0 +1,39 @@
+use snakers::{Frame, Point};
+use std::{fmt::Write, time::Instant};
+
+const SEGMENTS: u8 = 100;
+const ROUNDS: u32 = 10_000;
+
+// Draws a snake the way `Snake::render` does, one colored cell at a time
+fn snake(f: &mut Frame, cells: &[Point], jump_every_cell: bool) {
+  for p in cells {
+    write!(f, "\x1b[38;5;84m").unwrap();
+    if jump_every_cell {
+      write!(f, "\x1b[{};{}H▀", p.y, p.x).unwrap();
+    } else {
+      p.render('▀', f).unwrap();
+    }
+    write!(f, "\x1b[0m").unwrap();
+  }
+}
+
+fn measure(name: &str, cells: &[Point], jump_every_cell: bool) -> usize {
+  let mut f = Frame::default();
+  let start = Instant::now();
+  for _ in 0..ROUNDS {
+    f.truncate(0);
+    snake(&mut f, cells, jump_every_cell);
+  }
+  let elapsed = start.elapsed() / ROUNDS;
+  println!("{name:>10}: {:>5} bytes {elapsed:>10?}/frame", f.len());
+  f.len()
+}
+
+fn main() {
+  // A 100 segment snake folded into rows of 40 so most cells sit next to the previous one
+  let cells: Box<[Point]> = (0..SEGMENTS).map(|i| Point::new(10 + i % 40, 5 + i / 40)).collect();
+
+  let absolute = measure("absolute", &cells, true);
+  let tracked = measure("tracked", &cells, false);
+  println!("{:>10}: {:.1}% fewer bytes", "saved", 100. * (absolute - tracked) as f32 / absolute as f32);
+}
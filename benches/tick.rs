@@ -0,0 +1,46 @@
+use snakers::Game;
+use std::time::{Duration, Instant};
+
+const SEED: u64 = 406;
+const TICKS: u32 = 2_000;
+const SAMPLES: usize = 10;
+
+// Runs `op` TICKS times per sample on a fresh benchmark game and reports the median and spread per call
+fn measure(name: &str, paused: bool, setup: impl Fn(&mut Game), op: impl Fn(&mut Game, u32)) {
+  let mut samples: Vec<Duration> = (0..SAMPLES)
+    .map(|_| {
+      let mut game = Game::benchmark_setup(SEED);
+      setup(&mut game);
+      if !paused {
+        game.tick(Some(b'p')).unwrap();
+      }
+      let start = Instant::now();
+      for i in 0..TICKS {
+        op(&mut game, i);
+      }
+      start.elapsed() / TICKS
+    })
+    .collect();
+  samples.sort();
+  let (low, median, high) = (samples[0], samples[SAMPLES / 2], samples[SAMPLES - 1]);
+  println!("{name:>9}: {median:>10?}/call [{low:?} .. {high:?}]");
+}
+
+fn main() {
+  // The player zigzags so every tick is a step for all 8 snakes
+  let zigzag = |game: &mut Game, i: u32| {
+    game.tick(Some(if i.is_multiple_of(2) { b'd' } else { b's' })).unwrap();
+  };
+  measure("tick", false, |_| (), zigzag);
+  // While paused nothing moves, leaving only the render
+  measure("render", true, |_| (), |game, _| {
+    game.tick(None).unwrap();
+  });
+  // Tron trails keep growing, which is where scanning every body for crashes adds up
+  measure("tron", false, |game| {
+    game.tron(true).reset();
+  }, zigzag);
+  measure("tron+grid", false, |game| {
+    game.tron(true).collision_grid(true).reset();
+  }, zigzag);
+}
@@ -0,0 +1,65 @@
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CharSet {
+  #[default]
+  Unicode,
+  Ascii,
+}
+
+impl CharSet {
+  // Terminals whose locale doesn't mention UTF-8 are assumed to lack the glyphs
+  pub fn detect() -> Self {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+      .iter()
+      .filter_map(|var| std::env::var(var).ok())
+      .find(|locale| !locale.is_empty())
+      .unwrap_or_default()
+      .to_ascii_lowercase();
+    if locale.contains("utf-8") || locale.contains("utf8") {
+      CharSet::Unicode
+    } else {
+      CharSet::Ascii
+    }
+  }
+
+  pub fn glyph(self, c: char) -> char {
+    match self {
+      CharSet::Unicode => c,
+      CharSet::Ascii => ascii(c),
+    }
+  }
+
+  pub fn text(self, s: &str) -> Cow<'_, str> {
+    match self {
+      CharSet::Ascii if !s.is_ascii() => Cow::Owned(s.chars().map(ascii).collect()),
+      _ => Cow::Borrowed(s),
+    }
+  }
+}
+
+fn ascii(c: char) -> char {
+  match c {
+    c if c.is_ascii() => c,
+    '▀' | '▄' | '▘' | '▝' | '▖' | '▗' | '█' | '▓' | '▌' | '▐' => '#',
+    '▒' => ':',
+    '░' | '·' => '.',
+    '✦' => '*',
+    '✖' => 'x',
+    '╔' | '╗' | '╚' | '╝' => '+',
+    '═' => '-',
+    '║' => '|',
+    '↑' => '^',
+    '↓' => 'v',
+    '→' => '>',
+    '←' => '<',
+    '󰉛' => 'F',
+    '' => 'S',
+    '󱩡' => 'N',
+    '' => 'C',
+    '' => 'M',
+    '✂' => 'Y',
+    '⇄' => 'X',
+    _ => '?',
+  }
+}
@@ -50,3 +50,7 @@ pub const SNAKE_NAMES: [&str; 50] = [
   "Vicious",
   "Jaws",
 ];
+
+pub const SNAKE_COLORS: [u8; 24] = [
+  84, 51, 208, 195, 210, 190, 141, 39, 220, 203, 118, 177, 45, 214, 159, 219, 154, 75, 229, 183, 121, 209, 147, 87,
+];
@@ -1,7 +1,74 @@
 use crate::math::Point;
-use std::fmt::{self, Write};
+use std::{
+  fmt::{self, Write},
+  ops::{Deref, DerefMut},
+};
 
-pub fn mv(f: &mut String, p: &Point) -> fmt::Result {
+// Frame buffer that remembers where the cursor was left after drawing a narrow glyph, so a cell drawn
+// right next to the previous one can skip the cursor jump and let the terminal advance the cursor
+#[derive(Default)]
+pub struct Frame {
+  buf: String,
+  cursor: Option<(usize, Point)>,
+}
+
+impl Frame {
+  pub fn truncate(&mut self, len: usize) {
+    self.buf.truncate(len);
+    self.cursor = None;
+  }
+
+  // Only ASCII, box drawing and block elements are known to be a single column wide
+  pub fn advance(&mut self, from: &Point, c: char) {
+    self.cursor = (c.is_ascii_graphic() || matches!(c, '\u{2500}'..='\u{259f}')).then(|| (self.buf.len(), Point::new(from.x.wrapping_add(1), from.y)));
+  }
+
+  // Colors may change after a glyph is drawn but anything else could have moved the cursor
+  fn cursor_at(&self, p: &Point) -> bool {
+    let Some((len, cursor)) = self.cursor else {
+      return false;
+    };
+    cursor == *p
+      && self.buf.get(len..).is_some_and(|written| {
+        written
+          .split_inclusive('m')
+          .all(|sgr| sgr.strip_prefix("\x1b[").is_some_and(|sgr| sgr.bytes().all(|b| b.is_ascii_digit() || b == b';' || b == b'm')))
+      })
+  }
+}
+
+impl From<&str> for Frame {
+  fn from(s: &str) -> Self {
+    Self {
+      buf: String::from(s),
+      cursor: None,
+    }
+  }
+}
+
+impl Deref for Frame {
+  type Target = String;
+  fn deref(&self) -> &Self::Target {
+    &self.buf
+  }
+}
+
+impl DerefMut for Frame {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.buf
+  }
+}
+
+impl Write for Frame {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.buf.write_str(s)
+  }
+}
+
+pub fn mv(f: &mut Frame, p: &Point) -> fmt::Result {
+  if f.cursor_at(p) {
+    return Ok(());
+  }
   write!(f, "\x1b[{};{}H", p.y, p.x)
 }
 
@@ -16,3 +83,88 @@ pub fn bg(f: &mut String, id: u8) -> fmt::Result {
 pub fn reset(f: &mut String) -> fmt::Result {
   write!(f, "\x1b[0m")
 }
+
+// WCAG asks for at least 3:1 on large text, which is about what a single glyph needs to stand out
+pub const MIN_CONTRAST: f32 = 3.;
+
+// Contrast ratio between two palette colors from 1 (identical) up to 21 (black on white), using the
+// xterm defaults since the actual terminal theme can't be queried
+pub fn contrast(fg: u8, bg: u8) -> f32 {
+  let (a, b) = (luminance(fg), luminance(bg));
+  (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+pub(crate) fn luminance(id: u8) -> f32 {
+  let [r, g, b] = rgb(id).map(|c| {
+    let c = c as f32 / 255.;
+    if c <= 0.03928 {
+      c / 12.92
+    } else {
+      ((c + 0.055) / 1.055).powf(2.4)
+    }
+  });
+  0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+fn rgb(id: u8) -> [u8; 3] {
+  const SYSTEM: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [205, 0, 0],
+    [0, 205, 0],
+    [205, 205, 0],
+    [0, 0, 238],
+    [205, 0, 205],
+    [0, 205, 205],
+    [229, 229, 229],
+    [127, 127, 127],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [92, 92, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+  ];
+  const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+  match id {
+    0..=15 => SYSTEM[id as usize],
+    16..=231 => {
+      let i = id - 16;
+      [CUBE[(i / 36) as usize], CUBE[(i / 6 % 6) as usize], CUBE[(i % 6) as usize]]
+    }
+    _ => [8 + (id - 232) * 10; 3],
+  }
+}
+
+// Darkens a palette color to `amount` of its brightness, landing on the closest color of the 6x6x6 cube
+pub fn shade(id: u8, amount: f32) -> u8 {
+  const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+  let [r, g, b] = rgb(id).map(|c| {
+    let c = c as f32 * amount.clamp(0., 1.);
+    (0..CUBE.len()).min_by_key(|&i| (CUBE[i] as f32 - c).abs() as u16).unwrap_or(0) as u8
+  });
+  16 + r * 36 + g * 6 + b
+}
+
+// Counts the characters that take up space on screen, skipping escape sequences
+pub fn visible_len(s: &str) -> usize {
+  truncate_visible(s, usize::MAX).1
+}
+
+// Cuts a string after `width` visible characters, keeping any escape sequences before the cut
+pub fn truncate_visible(s: &str, width: usize) -> (&str, usize) {
+  let mut visible = 0;
+  let mut escape = false;
+  for (i, c) in s.char_indices() {
+    if escape {
+      escape = !c.is_ascii_alphabetic();
+    } else if c == '\x1b' {
+      escape = true;
+    } else if visible == width {
+      return (&s[..i], visible);
+    } else {
+      visible += 1;
+    }
+  }
+  (s, visible)
+}
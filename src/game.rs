@@ -1,218 +1,2157 @@
 use crate::{
-  esc::{fg, mv, reset},
-  map::{Arena, Effect, Food, Strategy},
-  math::{ColoredPoint, Direction, Point, Rng},
-  snake::Snake,
+  charset::CharSet,
+  esc::{contrast, fg, mv, reset, truncate_visible, visible_len, Frame, MIN_CONTRAST},
+  input::{Input, InputParser, Mouse},
+  map::{Arena, Effect, Food, FoodEffect, Occupancy, Particle, Strategy, WallPolicy, MAX_HEIGHT, MIN_ARENA},
+  math::{ColoredPoint, Direction, Point, Rect, Rng, WASD},
+  output::{FlushPolicy, FrameClock, FrameWriter, RestoreOnExit, RestoreOnPanic},
+  preferences::Preferences,
+  records::Records,
+  results::{self, Format},
+  snake::{CannibalStyle, Dash, DeathShrink, FoodContest, KillPolicy, OtherCollision, PackPlan, ScoreWeights, SelfCollision, Snake, StackRule, Stacking, StrategyParams},
 };
 use std::{
   fmt::{self, Display, Write},
   io,
+  path::{Path, PathBuf},
+  rc::Rc,
   time::{Duration, Instant},
 };
 
 pub struct Game {
   termsize: Point,
   rng: Rng,
-  top_halves: Vec<ColoredPoint>,
-  bottom_halves: Vec<ColoredPoint>,
   arena: Arena,
+  snakes: Vec<Snake>,
+  food: Vec<Food>,
+  opponents: Vec<Strategy>,
+  teams: Teams,
   delta: Instant,
+  clock: FrameClock,
+  ticked_at: Instant,
   running: bool,
   paused: bool,
   frame_duration_us: u128,
   debug: bool,
-  frame: String,
+  frame: Frame,
+  renderer: Option<Box<dyn Renderer>>,
+  frame_count: usize,
+  started_at: Instant,
+  follow: usize,
+  animate_resize: bool,
+  step_mode: bool,
+  step_ai: bool,
+  ai_reaction: u8,
+  spawn_regions: Vec<Rect>,
+  stepped: bool,
+  scoreboard: Placement,
+  legend: Legend,
+  centered: bool,
+  scoreboard_end: Point,
+  stats_pane: Placement,
+  stats_origin: Option<Point>,
+  stats_end: Point,
+  name_tags: bool,
+  ui_width: u16,
+  bite_cooldown: Duration,
+  food_contest: FoodContest,
+  pack_plan: PackPlan,
+  frame_times: FrameTimes,
+  low_fps: Option<f32>,
+  background: u8,
+  max_length: Option<usize>,
+  max_boost: u8,
+  score_weights: ScoreWeights,
+  strategy_params: StrategyParams,
+  spawn_protection: Duration,
+  simultaneous: bool,
+  smooth: bool,
+  straddle: bool,
+  body_gradient: bool,
+  strategy_glyphs: bool,
+  cannibal_min_len: usize,
+  god_mode: bool,
+  tron: bool,
+  self_collision: SelfCollision,
+  other_collision: OtherCollision,
+  dash: Dash,
+  dash_key: u8,
+  players: usize,
+  player_start_dir: Option<Direction>,
+  handicaps: Vec<(usize, usize)>,
+  endless: Option<u8>,
+  // Whether the sides wrapped before scrolling turned it off
+  scroll_wrap: bool,
+  // Frames run since the last scroll
+  scroll_frames: u32,
+  scrolled: usize,
+  magnets: usize,
+  splits: usize,
+  swaps: usize,
+  custom_food: Vec<(Rc<dyn FoodEffect>, usize)>,
+  split_strategy: Strategy,
+  player_keys: [[u8; 4]; MAX_PLAYERS],
+  input: InputParser,
+  mouse: bool,
+  pause_on_blur: bool,
+  demo: bool,
+  followed_at: Instant,
+  blurred: bool,
+  drag: Option<(Drag, Point)>,
+  eat_fx: bool,
+  food_lifetime: Option<Duration>,
+  rot: bool,
+  idle: Idle,
+  tick_based: bool,
+  input_rate_limit: bool,
+  turn_cooldown: u8,
+  death_banner: Duration,
+  restart_prompt: bool,
+  aspect: Option<f32>,
+  death_shrink: DeathShrink,
+  stacking: Stacking,
+  kill_policy: KillPolicy,
+  player_alive: bool,
+  died_at: Option<Instant>,
+  particles: Vec<Particle>,
+  ramp: Option<(Duration, u8)>,
+  // Frames run since the last level, paused frames don't count
+  ramp_ticks: u32,
+  level: u8,
+  target_length: Option<usize>,
+  summary: GameSummary,
+  records: Records,
+  preferences: Preferences,
+  results: Option<(PathBuf, Format)>,
+  flush_policy: FlushPolicy,
+  logo: String,
+  controls: Vec<String>,
+  cannibal_style: CannibalStyle,
 }
 
-const CLEAR: &str = "\x1b[?25l\x1b[2J";
-const TIME_US: u128 = 1_000_000;
+const CLEAR: &str = "\x1b[?25l\x1b[2J";
+const MOUSE_ON: &str = "\x1b[?1000h\x1b[?1006h";
+const MOUSE_OFF: &str = "\x1b[?1000l\x1b[?1006l";
+const FOCUS_ON: &str = "\x1b[?1004h";
+const FOCUS_OFF: &str = "\x1b[?1004l";
+const MAX_PLAYERS: usize = 4;
+// Up, left, down and right for each local player
+const PLAYER_KEYS: [[u8; 4]; MAX_PLAYERS] = [WASD, *b"8456", *b"IJKL", *b"TFGH"];
+const RESERVED_KEYS: &[u8] = b"hjklcfgpq\x1b[";
+const RESTORE: &str = "\x1b[0m\x1b[?25h\n";
+const TIME_US: u128 = 1_000_000;
+const MAX_FRAMESKIP: u32 = 5;
+const MAX_FPS: usize = 1000;
+// The strategy playing in place of each player in demo mode and how long the camera stays on a snake
+const DEMO_STRATEGY: Strategy = Strategy::Adaptive;
+const DEMO_FOLLOW: Duration = Duration::from_secs(8);
+
+impl Game {
+  pub fn new() -> Self {
+    let termsize: Point = readln::signals::term_size().unwrap().into();
+    let mut game = Self::headless(termsize.x, termsize.y);
+    game.charset(CharSet::detect());
+    game
+  }
+
+  pub fn headless(cols: u8, rows: u8) -> Self {
+    let termsize = Point::new(cols, rows);
+    Self {
+      termsize,
+      rng: Rng::new(),
+      arena: Arena::new(termsize.x / 4, termsize.y / 10, termsize.x / 2, (termsize.y as u16 * 4 / 5) as u8),
+      snakes: Vec::new(),
+      food: Vec::new(),
+      opponents: vec![
+        Strategy::Eat,
+        Strategy::Kill,
+        Strategy::Speed,
+        Strategy::Score,
+        Strategy::Cannibal,
+        Strategy::Adaptive,
+      ],
+      teams: Teams::Solo,
+      delta: Instant::now(),
+      clock: FrameClock::new(Duration::from_micros((TIME_US / 30) as u64), MAX_FRAMESKIP),
+      ticked_at: Instant::now(),
+      running: false,
+      paused: true,
+      frame_duration_us: TIME_US / 30,
+      debug: false,
+      frame: Frame::from(CLEAR),
+      renderer: Some(Box::<TerminalRenderer>::default()),
+      frame_count: 0,
+      started_at: Instant::now(),
+      follow: 0,
+      animate_resize: false,
+      step_mode: false,
+      step_ai: false,
+      ai_reaction: 1,
+      spawn_regions: Vec::new(),
+      stepped: false,
+      scoreboard: Placement::Right,
+      legend: Legend::TwoColumns,
+      centered: false,
+      scoreboard_end: Point::new(0, 0),
+      stats_pane: Placement::Hidden,
+      stats_origin: None,
+      stats_end: Point::new(0, 0),
+      name_tags: false,
+      ui_width: 0,
+      bite_cooldown: Duration::ZERO,
+      food_contest: FoodContest::FirstCome,
+      pack_plan: PackPlan::default(),
+      frame_times: FrameTimes::new(),
+      low_fps: Some(0.8),
+      background: 16,
+      max_length: None,
+      max_boost: u8::MAX,
+      score_weights: ScoreWeights::default(),
+      strategy_params: StrategyParams::default(),
+      spawn_protection: Duration::ZERO,
+      simultaneous: false,
+      smooth: false,
+      straddle: true,
+      body_gradient: false,
+      strategy_glyphs: false,
+      cannibal_min_len: 0,
+      god_mode: false,
+      tron: false,
+      self_collision: SelfCollision::Lethal,
+      other_collision: OtherCollision::Lethal,
+      dash: Dash::default(),
+      dash_key: b' ',
+      players: 1,
+      player_start_dir: None,
+      handicaps: Vec::new(),
+      endless: None,
+      scroll_wrap: true,
+      scroll_frames: 0,
+      scrolled: 0,
+      magnets: 0,
+      splits: 0,
+      swaps: 0,
+      custom_food: Vec::new(),
+      split_strategy: Strategy::Eat,
+      player_keys: PLAYER_KEYS,
+      input: InputParser::new(),
+      mouse: false,
+      pause_on_blur: false,
+      demo: false,
+      followed_at: Instant::now(),
+      blurred: false,
+      drag: None,
+      eat_fx: false,
+      food_lifetime: None,
+      rot: false,
+      idle: Idle::Moving,
+      tick_based: false,
+      input_rate_limit: false,
+      turn_cooldown: 0,
+      death_banner: Duration::from_secs(2),
+      restart_prompt: true,
+      aspect: None,
+      death_shrink: DeathShrink::PerMove(1),
+      stacking: Stacking::default(),
+      kill_policy: KillPolicy::default(),
+      player_alive: true,
+      died_at: None,
+      particles: Vec::new(),
+      ramp: None,
+      ramp_ticks: 0,
+      level: 0,
+      target_length: None,
+      summary: GameSummary::default(),
+      records: Records::default(),
+      preferences: Preferences::default(),
+      results: None,
+      flush_policy: FlushPolicy::PerFrame,
+      logo: LOGO.to_string(),
+      controls: CONTROLS.iter().map(|c| c.to_string()).collect(),
+      cannibal_style: CannibalStyle::default(),
+    }
+  }
+
+  // Spawns the player and one AI snake per strategy, 8 in total, with the usual 12 food in the benchmark
+  // arena on a 250x80 terminal. Every snake moves on each stepped tick so the same seed always does the same work.
+  pub fn benchmark_setup(seed: u64) -> Self {
+    let mut game = Self::headless(250, 80);
+    game.arena = Arena::benchmark();
+    game.seed(seed).step_mode(true).step_ai(true).opponents(&[
+      Strategy::Speed,
+      Strategy::Score,
+      Strategy::Eat,
+      Strategy::Kill,
+      Strategy::Cannibal,
+      Strategy::Adaptive,
+      Strategy::weighted(1., 1., 1.),
+    ]);
+    game.reset();
+    game
+  }
+
+  pub fn fps(&mut self, fps: usize) -> &mut Self {
+    self.frame_duration_us = TIME_US / fps.clamp(1, MAX_FPS) as u128;
+    self.clock.set_frame(Duration::from_micros(self.frame_duration_us as u64));
+    self
+  }
+
+  pub fn try_fps(&mut self, fps: usize) -> GameResult<&mut Self> {
+    if !(1..=MAX_FPS).contains(&fps) {
+      return Err(ConfigError::Fps(fps).into());
+    }
+    Ok(self.fps(fps))
+  }
+
+  // Warns when the measured fps stays under this share of the target, None never warns
+  pub fn low_fps_warning(&mut self, ratio: Option<f32>) -> &mut Self {
+    self.low_fps = ratio;
+    self
+  }
+
+  // Palette color the terminal is assumed to draw on, snakes whose color blends into it get a warning
+  pub fn background(&mut self, color: u8) -> &mut Self {
+    self.background = color;
+    self
+  }
+
+  pub fn max_frameskip(&mut self, frames: u32) -> &mut Self {
+    self.clock.set_max_frameskip(frames);
+    self
+  }
+
+  // Longest gap between loop iterations that still owes frames, anything past it is dropped
+  pub fn max_catchup(&mut self, max: Duration) -> &mut Self {
+    self.clock.set_max_catchup(max);
+    self
+  }
+
+  // Paces snakes by simulation ticks, one per frame, instead of wall time so seeded replays are exact
+  pub fn tick_based_speed(&mut self, tick_based: bool) -> &mut Self {
+    self.tick_based = tick_based;
+    self
+  }
+
+  pub fn seed(&mut self, seed: u64) -> &mut Self {
+    self.rng = Rng::seeded(seed);
+    self
+  }
+
+  pub fn opponents(&mut self, opponents: &[Strategy]) -> &mut Self {
+    self.opponents = opponents.to_vec();
+    self
+  }
+
+  pub fn teams(&mut self, teams: Teams) -> &mut Self {
+    self.teams = teams;
+    self
+  }
+
+  pub fn cannibal_style(&mut self, style: CannibalStyle) -> &mut Self {
+    self.cannibal_style = style;
+    self
+  }
+
+  pub fn animate_resize(&mut self, animate: bool) -> &mut Self {
+    self.animate_resize = animate;
+    self
+  }
+
+  pub fn step_mode(&mut self, step: bool) -> &mut Self {
+    self.step_mode = step;
+    self
+  }
+
+  pub fn step_ai(&mut self, step: bool) -> &mut Self {
+    self.step_ai = step;
+    self
+  }
+
+  // Moves between each AI decision, 1 rethinks on every move
+  pub fn ai_reaction(&mut self, ticks: u8) -> &mut Self {
+    self.ai_reaction = ticks;
+    self
+  }
+
+  // Snakes spawn and respawn within the region at their index, snakes past the end use the whole arena
+  pub fn spawn_regions(&mut self, regions: Vec<Rect>) -> &mut Self {
+    self.spawn_regions = regions;
+    self
+  }
+
+  pub fn simultaneous(&mut self, simultaneous: bool) -> &mut Self {
+    self.simultaneous = simultaneous;
+    self
+  }
+
+  // Tracks which snakes cover each cell so crash checks don't scan every body, worth it once snakes get long
+  pub fn collision_grid(&mut self, enabled: bool) -> &mut Self {
+    self.arena.occupancy = enabled.then(Occupancy::default);
+    self
+  }
+
+  pub fn smooth(&mut self, smooth: bool) -> &mut Self {
+    self.smooth = smooth;
+    self
+  }
+
+  // Snakes crossing a wrapped edge show on both sides, turning it off hides the part still leaving
+  pub fn wrap_straddle(&mut self, straddle: bool) -> &mut Self {
+    self.straddle = straddle;
+    self
+  }
+
+  // Snakes shorter than this eat cannibal food like plain food, it shows greyed out while the watched
+  // snake is too short
+  pub fn cannibal_min_length(&mut self, len: usize) -> &mut Self {
+    self.cannibal_min_len = len;
+    self
+  }
+
+  // Shades every snake from its own color at the head down to a darker tail
+  pub fn body_gradient(&mut self, gradient: bool) -> &mut Self {
+    self.body_gradient = gradient;
+    self
+  }
+
+  // Draws each snake with its strategy's glyph instead of half blocks, for telling them apart without color
+  pub fn strategy_glyphs(&mut self, glyphs: bool) -> &mut Self {
+    self.strategy_glyphs = glyphs;
+    self
+  }
+
+  pub fn god_mode(&mut self, god_mode: bool) -> &mut Self {
+    self.god_mode = god_mode;
+    if let Some(player) = self.snakes.first_mut() {
+      player.god_mode = god_mode;
+    }
+    self
+  }
+
+  // Snakes keep their whole trail and stay out once they crash, the last one moving wins
+  pub fn tron(&mut self, tron: bool) -> &mut Self {
+    self.tron = tron;
+    self
+  }
+
+  // The first `n` snakes are steered locally, up to 4
+  pub fn players(&mut self, n: usize) -> &mut Self {
+    self.players = n.clamp(1, MAX_PLAYERS);
+    self
+  }
+
+  // Which way the players face when they spawn instead of a random direction
+  pub fn player_start_dir(&mut self, dir: Direction) -> &mut Self {
+    self.player_start_dir = Some(dir);
+    self
+  }
+
+  // Spawns the snake at `idx` with `extra_len` more segments laid out behind it, players come first and then
+  // the opponents in order
+  pub fn handicap(&mut self, idx: usize, extra_len: usize) -> &mut Self {
+    self.handicaps.retain(|&(i, _)| i != idx);
+    self.handicaps.push((idx, extra_len));
+    self
+  }
+
+  // Scrolls the arena a column to the right every `scroll_rate` frames, zero stops it. Rather than moving
+  // the camera the world shifts a column left under a fixed arena: bodies, food and rotten cells all move,
+  // so positions stay within the arena and everything keeps working in arena coordinates. Snakes left
+  // behind the trailing edge die and food passing it comes back at the leading edge, the sides stop
+  // wrapping so there's no way around it until scrolling stops again
+  pub fn endless(&mut self, scroll_rate: u8) -> &mut Self {
+    match (self.endless.is_some(), scroll_rate > 0) {
+      (false, true) => (self.scroll_wrap, self.arena.wrap_x) = (self.arena.wrap_x, false),
+      (true, false) => self.arena.wrap_x = self.scroll_wrap,
+      _ => (),
+    }
+    self.endless = (scroll_rate > 0).then_some(scroll_rate);
+    self
+  }
+
+  // Columns the world has shifted left since the game started, add it to an x to get a distance travelled
+  pub fn scrolled(&self) -> usize {
+    self.scrolled
+  }
+
+  // Extra food that makes the snake eating it pull nearby food in for a while
+  pub fn magnets(&mut self, n: usize) -> &mut Self {
+    self.magnets = n;
+    self
+  }
+
+  // Extra food that splits the back half of whoever eats it into a new AI snake
+  pub fn splits(&mut self, n: usize) -> &mut Self {
+    self.splits = n;
+    self
+  }
+
+  // Extra food that makes whoever eats it trade places with the nearest rival
+  pub fn swaps(&mut self, n: usize) -> &mut Self {
+    self.swaps = n;
+    self
+  }
+
+  // Extra food with a user defined effect, registering the same effect again adds more of it
+  pub fn custom_food(&mut self, effect: impl FoodEffect + 'static, n: usize) -> &mut Self {
+    self.custom_food.push((Rc::new(effect), n));
+    self
+  }
+
+  // Who gets food that several snakes reach in the same tick
+  pub fn food_contest(&mut self, contest: FoodContest) -> &mut Self {
+    self.food_contest = contest;
+    self
+  }
+
+  pub fn split_strategy(&mut self, strat: Strategy) -> &mut Self {
+    self.split_strategy = strat;
+    self
+  }
+
+  // Replaces the key sets of the first players, each given as up, left, down and right
+  pub fn player_keys(&mut self, keys: &[[u8; 4]]) -> GameResult<&mut Self> {
+    let mut player_keys = self.player_keys;
+    for (set, keys) in player_keys.iter_mut().zip(keys) {
+      *set = *keys;
+    }
+    let mut bound: Vec<u8> = RESERVED_KEYS.iter().copied().chain([self.dash_key]).collect();
+    for &key in player_keys.iter().flatten() {
+      if bound.contains(&key) {
+        return Err(GameError::KeyConflict(key));
+      }
+      bound.push(key);
+    }
+    self.player_keys = player_keys;
+    Ok(self)
+  }
+
+  pub fn dash(&mut self, charges: u8, recharge: Duration, distance: u8) -> &mut Self {
+    self.dash = Dash { charges, recharge, distance };
+    for snake in &mut self.snakes {
+      snake.dash = self.dash;
+      snake.refill_dash();
+    }
+    self
+  }
+
+  pub fn dash_key(&mut self, key: u8) -> &mut Self {
+    self.dash_key = key;
+    self
+  }
+
+  pub fn max_length(&mut self, max: Option<usize>) -> &mut Self {
+    self.max_length = max;
+    self
+  }
+
+  pub fn score_weights(&mut self, weights: ScoreWeights) -> &mut Self {
+    self.score_weights = weights;
+    self
+  }
+
+  // Ramping up the level still narrows each AI's kill margin from here
+  pub fn strategy_params(&mut self, params: StrategyParams) -> &mut Self {
+    self.strategy_params = params;
+    self
+  }
+
+  pub fn max_boost(&mut self, max: u8) -> &mut Self {
+    self.max_boost = max;
+    self
+  }
+
+  pub fn spawn_protection(&mut self, protection: Duration) -> &mut Self {
+    self.spawn_protection = protection;
+    self
+  }
+
+  pub fn bite_cooldown(&mut self, cooldown: Duration) -> &mut Self {
+    self.bite_cooldown = cooldown;
+    self
+  }
+
+  pub fn self_collision(&mut self, policy: SelfCollision) -> &mut Self {
+    self.self_collision = policy;
+    self
+  }
+
+  pub fn other_collision(&mut self, policy: OtherCollision) -> &mut Self {
+    self.other_collision = policy;
+    self
+  }
+
+  pub fn wall_policy(&mut self, policy: WallPolicy) -> &mut Self {
+    self.arena.wall_policy = policy;
+    self
+  }
+
+  // Keeps the arena at full size and scrolls a window over it instead of shrinking it to fit the terminal
+  pub fn viewport(&mut self, viewport: bool) -> &mut Self {
+    self.arena.viewport = viewport;
+    self
+  }
+
+  // Draws every logical cell as an x by y block of terminal cells, the arena shrinks to fit if it no longer does
+  pub fn scale(&mut self, x: u8, y: u8) -> &mut Self {
+    self.arena.scale = Point::new(x.max(1), y.max(1));
+    self
+  }
+
+  pub fn arena_size(&mut self, width: u8, height: u8) -> &mut Self {
+    self.arena.resize_to(Point::new(width, height), 1);
+    self.arena.animate_resize(&mut self.food);
+    self
+  }
+
+  pub fn try_arena_size(&mut self, width: u8, height: u8) -> GameResult<&mut Self> {
+    if width < MIN_ARENA || !(MIN_ARENA..=MAX_HEIGHT).contains(&height) {
+      return Err(ConfigError::ArenaSize(Point::new(width, height)).into());
+    }
+    Ok(self.arena_size(width, height))
+  }
+
+  pub fn border_inset(&mut self, inset: u8) -> &mut Self {
+    let inset = std::cmp::min(inset, self.max_inset());
+    self.arena.set_inset(inset, &mut self.food);
+    self
+  }
+
+  pub fn try_border_inset(&mut self, inset: u8) -> GameResult<&mut Self> {
+    if inset > self.max_inset() {
+      return Err(ConfigError::Inset(inset).into());
+    }
+    Ok(self.border_inset(inset))
+  }
+
+  // Widest inset that still leaves the smallest arena inside the current border
+  fn max_inset(&self) -> u8 {
+    let footprint = std::cmp::min(self.arena.size.x, self.arena.size.y).saturating_add(self.arena.inset.saturating_mul(2));
+    footprint.saturating_sub(MIN_ARENA) / 2
+  }
+
+  pub fn grid(&mut self, spacing: Option<u8>) -> &mut Self {
+    self.arena.grid = spacing;
+    self
+  }
+
+  pub fn wrap_axes(&mut self, x: bool, y: bool) -> &mut Self {
+    self.arena.wrap_x = x;
+    self.arena.wrap_y = y;
+    self
+  }
+
+  pub fn ramp(&mut self, enabled: bool, interval: Duration, step: u8) -> &mut Self {
+    self.ramp = enabled.then_some((interval, step));
+    self
+  }
+
+  pub fn target_length(&mut self, length: Option<usize>) -> &mut Self {
+    self.target_length = length;
+    self
+  }
+
+  pub fn summary(&self) -> &GameSummary {
+    &self.summary
+  }
+
+  pub fn frame_count(&self) -> usize {
+    self.frame_count
+  }
+
+  pub fn is_running(&self) -> bool {
+    self.running
+  }
+
+  // Time since the current round started
+  pub fn elapsed(&self) -> Duration {
+    self.started_at.elapsed()
+  }
+
+  pub fn level(&self) -> u8 {
+    self.level
+  }
+
+  pub fn flush_policy(&mut self, policy: FlushPolicy) -> &mut Self {
+    self.flush_policy = policy;
+    self
+  }
+
+  pub fn records_file(&mut self, path: impl AsRef<Path>) -> &mut Self {
+    self.records = Records::load(path.as_ref());
+    self
+  }
+
+  pub fn records(&self) -> &Records {
+    &self.records
+  }
+
+  pub fn save_records(&self) -> io::Result<()> {
+    self.records.save()
+  }
+
+  // Applies the preferences saved at `path` and writes the current ones back there when the game exits,
+  // key bindings that conflict with each other are left at their defaults
+  pub fn with_preferences(&mut self, path: impl AsRef<Path>) -> &mut Self {
+    let preferences = Preferences::load(path.as_ref());
+    if let Some(key) = preferences.dash_key {
+      self.dash_key(key);
+    }
+    let _ = self.player_keys(&preferences.player_keys);
+    if let Some(fps) = preferences.fps {
+      self.fps(fps);
+    }
+    if let Some(size) = preferences.arena_size {
+      self.arena_size(size.x, size.y);
+    }
+    if let Some(charset) = preferences.charset {
+      self.charset(charset);
+    }
+    self.preferences = preferences;
+    self
+  }
+
+  // The choices with_preferences would restore, as they stand now
+  pub fn preferences(&self) -> Preferences {
+    let mut preferences = self.preferences.clone();
+    preferences.player_keys = self.player_keys.to_vec();
+    preferences.dash_key = Some(self.dash_key);
+    preferences.fps = Some((TIME_US / self.frame_duration_us.max(1)) as usize);
+    preferences.arena_size = Some(self.arena.resize_target());
+    preferences.charset = Some(self.arena.charset);
+    preferences
+  }
+
+  pub fn save_preferences(&self) -> io::Result<()> {
+    self.preferences().save()
+  }
+
+  // Final standings written when the game exits
+  pub fn export_results(&mut self, path: impl AsRef<Path>, format: Format) -> &mut Self {
+    self.results = Some((path.as_ref().to_path_buf(), format));
+    self
+  }
+
+  pub fn save_results(&self) -> io::Result<()> {
+    match &self.results {
+      Some((path, format)) => results::export(path, *format, &self.snakes),
+      None => Ok(()),
+    }
+  }
+
+  pub fn logo(&mut self, logo: &str) -> &mut Self {
+    self.logo = logo.to_string();
+    self
+  }
+
+  pub fn controls(&mut self, controls: Vec<String>) -> &mut Self {
+    self.controls = controls;
+    self
+  }
+
+  pub fn eat_fx(&mut self, eat_fx: bool) -> &mut Self {
+    self.eat_fx = eat_fx;
+    self
+  }
+
+  pub fn mouse(&mut self, mouse: bool) -> &mut Self {
+    self.mouse = mouse;
+    self
+  }
+
+  // Pauses while the terminal is out of focus, only games paused this way resume on focus
+  pub fn pause_on_blur(&mut self, pause: bool) -> &mut Self {
+    self.pause_on_blur = pause;
+    self
+  }
+
+  // Runs without anyone playing: the players are replaced by AI snakes, only quitting is left to the keyboard,
+  // the camera moves on to another snake every few seconds and a finished game starts over
+  pub fn demo(&mut self, demo: bool) -> &mut Self {
+    self.demo = demo;
+    self.paused = !demo;
+    self
+  }
+
+  pub fn food_lifetime(&mut self, lifetime: Option<Duration>) -> &mut Self {
+    self.food_lifetime = lifetime;
+    self
+  }
+
+  // Expired food rots into a lethal cell for a while before it respawns elsewhere
+  pub fn rot(&mut self, rot: bool) -> &mut Self {
+    self.rot = rot;
+    self
+  }
+
+  pub fn idle_behavior(&mut self, idle: Idle) -> &mut Self {
+    self.idle = idle;
+    self
+  }
+
+  // At most one turn per move for each player, rapid presses replace the pending turn instead of queueing
+  pub fn input_rate_limit(&mut self, limit: bool) -> &mut Self {
+    self.input_rate_limit = limit;
+    self
+  }
+
+  // Moves a snake has to make after turning before it can turn again, steers in between are dropped
+  pub fn turn_cooldown(&mut self, ticks: u8) -> &mut Self {
+    self.turn_cooldown = ticks;
+    self
+  }
+
+  // How long the banner over the arena stays up after the player dies, zero never shows it
+  pub fn death_banner(&mut self, duration: Duration) -> &mut Self {
+    self.death_banner = duration;
+    self
+  }
+
+  // Keeps the final frame up once the game ends until R restarts it or Q quits, otherwise run returns right away
+  pub fn restart_prompt(&mut self, prompt: bool) -> &mut Self {
+    self.restart_prompt = prompt;
+    self
+  }
+
+  pub fn death_shrink(&mut self, shrink: DeathShrink) -> &mut Self {
+    self.death_shrink = shrink;
+    self
+  }
+
+  // Speed food stacks while cannibal and magnet food refresh their timers unless told otherwise
+  pub fn effect_stacking(&mut self, effect: Effect, rule: StackRule) -> &mut Self {
+    self.stacking.set(effect, rule);
+    self
+  }
+
+  pub fn kill_policy(&mut self, policy: KillPolicy) -> &mut Self {
+    self.kill_policy = policy;
+    self
+  }
+
+  // Resizing one side of the arena with h/j/k/l scales the other to keep this width to height ratio on screen
+  pub fn lock_aspect(&mut self, ratio: Option<f32>) -> &mut Self {
+    self.aspect = ratio.filter(|ratio| ratio.is_finite() && *ratio > 0.);
+    self
+  }
+
+  pub fn try_lock_aspect(&mut self, ratio: Option<f32>) -> GameResult<&mut Self> {
+    match ratio {
+      Some(ratio) if !ratio.is_finite() || ratio <= 0. => Err(ConfigError::AspectRatio(ratio).into()),
+      _ => Ok(self.lock_aspect(ratio)),
+    }
+  }
+
+  pub fn charset(&mut self, charset: CharSet) -> &mut Self {
+    self.arena.charset = charset;
+    self
+  }
+
+  pub fn renderer(&mut self, renderer: Box<dyn Renderer>) -> &mut Self {
+    self.renderer = Some(renderer);
+    self
+  }
+
+  pub fn scoreboard(&mut self, placement: Placement) -> &mut Self {
+    self.scoreboard = placement;
+    self
+  }
+
+  // Live totals drawn beside the arena, after the scoreboard when both share a side
+  pub fn stats_pane(&mut self, placement: Placement) -> &mut Self {
+    self.stats_pane = placement;
+    self
+  }
+
+  // Draws each snake's name just above its head, hidden while two heads are close enough to overlap
+  pub fn name_tags(&mut self, enabled: bool) -> &mut Self {
+    self.name_tags = enabled;
+    self
+  }
+
+  pub fn legend(&mut self, legend: Legend) -> &mut Self {
+    self.legend = legend;
+    self
+  }
+
+  // Centers the arena together with its scoreboard, keeping it centered when the terminal is resized
+  pub fn center_arena(&mut self) -> &mut Self {
+    let size = self.arena.window();
+    let width = self.scoreboard_width();
+    let (before, after, below) = match self.scoreboard {
+      Placement::Hidden => (0, 0, 0),
+      Placement::Right => (0, width as u16, 0),
+      Placement::Left => (width as u16 + 2, 0, 0),
+      Placement::Below => {
+        let columns = std::cmp::max(1, (size.x as usize + 2) / (width as usize + 2));
+        (0, 0, self.snakes.len().div_ceil(columns) as u16)
+      }
+    };
+    let footprint = (before + size.x as u16 + 2 + after, size.y as u16 + 2 + below);
+    let margin = |term: u8, used: u16| (term as u16).saturating_sub(used) / 2;
+    let x = margin(self.termsize.x, footprint.0) + before + 1;
+    let y = margin(self.termsize.y, footprint.1) + 1;
+    self.arena.position = Point::new(x.min(u8::MAX as u16) as u8, y.min(u8::MAX as u16) as u8);
+    self.centered = true;
+    self
+  }
+
+  // Columns and rows the rendered game takes up, from the two UI lines above the arena down to the
+  // scoreboard and stats wherever they are placed
+  pub fn render_bounds(&self) -> (u16, u16) {
+    let Point { x, y } = self.arena.position;
+    let window = self.arena.window();
+    let (x, y, w, h) = (x as u16, y as u16, window.x as u16, window.y as u16);
+    let starts = self.scoreboard_entries().into_iter().map(|(position, _, _)| position).chain(self.stats_origin);
+    let left = starts.map(|position| position.x as u16).chain([x]).min().unwrap();
+    let top = std::cmp::max(y.saturating_sub(2), 1);
+    let arena_right = x + w + 1;
+    let ui_right = (x + self.ui_width).saturating_sub(1);
+    let right = [arena_right, self.scoreboard_end.x as u16, self.stats_end.x as u16, ui_right].into_iter().max().unwrap();
+    let bottom = [y + h + 1, self.scoreboard_end.y as u16, self.stats_end.y as u16].into_iter().max().unwrap();
+    (right - left + 1, bottom - top + 1)
+  }
+
+  pub fn camera(&self) -> Point {
+    self.arena.camera
+  }
+
+  pub fn snakes(&self) -> &[Snake] {
+    &self.snakes
+  }
+
+  pub fn food(&self) -> &[Food] {
+    &self.food
+  }
+
+  // Places a specific food on a free cell, spawning the snakes first if the game hasn't started
+  pub fn spawn_food(&mut self, effect: Effect, position: Point) -> GameResult {
+    self.place_food(Food::new(effect, position))
+  }
+
+  pub fn spawn_custom_food(&mut self, effect: impl FoodEffect + 'static, position: Point) -> GameResult {
+    self.place_food(Food::custom(Rc::new(effect), position))
+  }
+
+  fn place_food(&mut self, food: Food) -> GameResult {
+    if self.snakes.is_empty() {
+      self.reset();
+    }
+    let position = food.position;
+    if position.x >= self.arena.size.x || position.y >= self.arena.height() {
+      return Err(GameError::OutOfBounds(position));
+    }
+    if self.occupied_cells().any(|p| p == position) {
+      return Err(GameError::Occupied(position));
+    }
+    self.food.push(food);
+    Ok(())
+  }
+
+  pub fn occupied_cells(&self) -> impl Iterator<Item = Point> + '_ {
+    let bodies = self.snakes.iter().flat_map(|snake| snake.body.iter().copied());
+    bodies.chain(self.food.iter().map(|food| food.position))
+  }
+
+  pub fn particles(&self) -> &[Particle] {
+    &self.particles
+  }
+
+  pub fn reset(&mut self) {
+    let player = if self.demo { DEMO_STRATEGY } else { Strategy::Player };
+    let strategies = std::iter::repeat_n(player, self.players).chain(self.opponents.iter().copied());
+    self.snakes = strategies
+      .enumerate()
+      .map(|(i, strat)| {
+        let dir = if i < self.players { self.player_start_dir } else { None };
+        let mut snake = Snake::random(8, strat, dir, &mut self.rng, &self.arena.size);
+        if let Some(&region) = self.spawn_regions.get(i) {
+          snake.spawn_region = Some(region);
+          snake.respawn(&mut self.rng, &self.arena);
+          let head = *snake.head();
+          snake.body.iter_mut().for_each(|p| *p = head);
+        }
+        snake.max_len = self.max_length;
+        snake.max_boost = self.max_boost;
+        snake.score_weights = self.score_weights;
+        snake.strategy_params = self.strategy_params;
+        snake.spawn_protection = self.spawn_protection;
+        snake.smooth = self.smooth;
+        snake.gradient = self.body_gradient;
+        snake.straddle = self.straddle;
+        snake.glyph = self.strategy_glyphs.then(|| strat.glyph());
+        snake.cannibal_min_len = self.cannibal_min_len;
+        snake.tron = self.tron;
+        snake.self_collision = self.self_collision;
+        snake.other_collision = self.other_collision;
+        snake.dash = self.dash;
+        snake.refill_dash();
+        snake.reaction = self.ai_reaction;
+        snake.rate_limited = self.input_rate_limit;
+        snake.turn_cooldown = self.turn_cooldown;
+        snake.death_shrink = self.death_shrink;
+        snake.stacking = self.stacking;
+        snake.kill_policy = self.kill_policy;
+        snake
+      })
+      .collect();
+    const PLAYER_NAMES: [&str; MAX_PLAYERS] = ["P1", "P2", "P3", "P4"];
+    for (i, player) in self.snakes.iter_mut().take(self.players).enumerate() {
+      player.name = if self.players > 1 { PLAYER_NAMES[i] } else { "You" };
+    }
+    self.snakes[0].god_mode = self.god_mode;
+    Snake::assign_colors(&mut self.snakes);
+    for i in 0..self.snakes.len() {
+      self.snakes[i].team = match &self.teams {
+        Teams::Solo => i as u8,
+        Teams::ByStrategy => self.snakes.iter().position(|snake| snake.strategy() == self.snakes[i].strategy()).unwrap() as u8,
+        Teams::Assigned(teams) => teams.get(i).copied().unwrap_or(u8::MAX - i as u8),
+      };
+    }
+    for &(idx, extra_len) in &self.handicaps {
+      if let Some(snake) = self.snakes.get_mut(idx) {
+        snake.grow_behind(extra_len, &self.arena);
+      }
+    }
+    self.food = (0..12)
+      .map(Effect::from)
+      .chain(std::iter::repeat_n(Effect::Magnet, self.magnets))
+      .chain(std::iter::repeat_n(Effect::Split, self.splits))
+      .chain(std::iter::repeat_n(Effect::Swap, self.swaps))
+      .map(|effect| Food::random_free(effect, &mut self.rng, &self.arena.size, &self.snakes))
+      .collect();
+    for (effect, n) in &self.custom_food {
+      for _ in 0..*n {
+        let mut food = Food::custom(Rc::clone(effect), Point::new(0, 0));
+        food.relocate(&mut self.rng, &self.arena.size, &self.snakes);
+        self.food.push(food);
+      }
+    }
+    self.arena.rotten.clear();
+    self.pack_plan.clear();
+    if let Some(occupancy) = &mut self.arena.occupancy {
+      occupancy.invalidate();
+    }
+    self.player_alive = true;
+    self.died_at = None;
+    self.follow = 0;
+    self.level = 0;
+    self.summary = GameSummary::default();
+    (self.scroll_frames, self.scrolled) = (0, 0);
+    self.frame_count = 0;
+    self.started_at = Instant::now();
+    self.ramp_ticks = 0;
+  }
+
+  pub fn run(&mut self) -> GameResult {
+    self.running = true;
+    self.reset();
+    let mut restore = RESTORE.to_string();
+    if self.mouse {
+      restore.insert_str(0, MOUSE_OFF);
+    }
+    if self.pause_on_blur {
+      restore.insert_str(0, FOCUS_OFF);
+    }
+    let _restore = RestoreOnPanic::install(io::stdout(), &restore);
+    let _restore_on_exit = RestoreOnExit::new(io::stdout(), &restore);
+    let mut out = FrameWriter::new(io::BufWriter::with_capacity(1 << 16, io::stdout().lock()), self.flush_policy);
+    if self.mouse {
+      out.write_frame(MOUSE_ON)?;
+    }
+    if self.pause_on_blur {
+      out.write_frame(FOCUS_ON)?;
+    }
+
+    self.ticked_at = Instant::now();
+    while self.running {
+      let input = match readln::getch(0) {
+        Ok(b) => Some(b),
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => None,
+        Err(err) => return Err(GameError::Io(err)),
+      };
+      let now = Instant::now();
+      let elapsed = now - self.ticked_at;
+      self.ticked_at = now;
+
+      if readln::signals::term_resized(0) {
+        self.termsize = readln::signals::term_size().unwrap().into();
+        if self.centered {
+          self.center_arena();
+        }
+      }
+
+      if self.advance(elapsed, input)?.is_some() {
+        self.frame.push('\n');
+        out.write_frame(&self.frame)?;
+      }
+    }
+
+    out.flush()?;
+    self.save_records()?;
+    self.save_preferences()?;
+    self.save_results()?;
+    Ok(())
+  }
+
+  // Advances the game by a single step without touching the terminal, spawning the snakes on the
+  // first call. Movement still follows each snake's speed unless step mode is enabled.
+  pub fn tick(&mut self, input: Option<u8>) -> GameResult<&str> {
+    if self.snakes.is_empty() {
+      self.reset();
+      self.running = true;
+    }
+    self.stepped = false;
+    if let Some(b) = input {
+      self.handle_input(b);
+    }
+    self.update(1);
+    let delta = self.delta.elapsed().as_micros();
+    self.render(delta)?;
+    self.delta = Instant::now();
+    Ok(&self.frame)
+  }
+
+  // Runs `elapsed` wall time through the frame clock the way run does. The game updates on every call but
+  // only the frames that came due count as game time, and a frame is rendered once at least one did
+  pub fn advance(&mut self, elapsed: Duration, input: Option<u8>) -> GameResult<Option<&str>> {
+    if self.snakes.is_empty() {
+      self.reset();
+      self.running = true;
+    }
+    self.stepped = false;
+    if let Some(b) = input {
+      self.handle_input(b);
+    }
+    let due = self.clock.advance(elapsed);
+    if self.tick_based {
+      for _ in 0..due {
+        self.update(1);
+      }
+    } else {
+      self.update(due);
+    }
+    if due == 0 {
+      return Ok(None);
+    }
+    self.render(self.delta.elapsed().as_micros())?;
+    self.delta = Instant::now();
+    Ok(Some(&self.frame))
+  }
+
+  // `frames` is how much game time the update covers, snakes not paced by ticks still move on wall time
+  fn update(&mut self, frames: u32) {
+    if self.demo {
+      self.update_demo();
+    }
+    if self.paused || self.summary.finished {
+      return;
+    }
+
+    self.update_ramp(frames);
+    self.update_scroll(frames);
+    let size = Point::new(self.arena.size.x, self.arena.height());
+    if let Some(occupancy) = &mut self.arena.occupancy {
+      occupancy.refresh(&self.snakes, size);
+    }
+
+    let mut moving = Vec::with_capacity(self.snakes.len());
+    for i in 0..self.snakes.len() {
+      let human = self.snakes[i].is_human();
+      let idle = human && !self.snakes[i].took_control();
+      if idle && self.idle == Idle::Still {
+        continue;
+      }
+      let moves = if self.step_mode && (human || self.step_ai) {
+        self.stepped
+      } else if self.tick_based {
+        self.snakes[i].can_move_ticks(Duration::from_micros(self.frame_duration_us as u64))
+      } else {
+        self.snakes[i].can_move()
+      };
+      if moves {
+        if !human && self.snakes[i].think() {
+          let target = Snake::coordinated_target(&self.snakes, i, &self.food, &mut self.pack_plan);
+          Snake::seek(&mut self.snakes, i, &target, &self.arena);
+        } else if let (true, Idle::Autopilot(strat)) = (idle, self.idle) {
+          if strat != Strategy::Player {
+            let target = self.snakes[i].target_as(strat, &self.snakes, &self.food);
+            Snake::seek(&mut self.snakes, i, &target, &self.arena);
+          }
+        }
+        self.eat(i);
+        let dash = self.snakes[i].take_dash();
+        if self.simultaneous {
+          moving.push((i, dash));
+        } else {
+          Snake::serpentine(&mut self.snakes, i, &mut self.rng, &mut self.arena);
+          self.finish_dash(i, dash);
+        }
+      }
+    }
+    if !moving.is_empty() {
+      let indices: Vec<usize> = moving.iter().map(|&(i, _)| i).collect();
+      Snake::serpentine_all(&mut self.snakes, &indices, &mut self.rng, &mut self.arena);
+      // Dashes finish one snake at a time once everyone has taken their first step
+      for (i, dash) in moving {
+        self.finish_dash(i, dash);
+      }
+    }
+
+    let alive = self.snakes[0].is_alive();
+    if self.player_alive && !alive {
+      self.died_at = Some(Instant::now());
+    }
+    self.player_alive = alive;
+
+    self.expire_food();
+    self.check_target_length();
+    self.check_elimination();
+
+    for snake in &self.snakes {
+      self.records.update(snake.strategy().name(), snake.score());
+    }
+  }
+
+  fn eat(&mut self, i: usize) {
+    let eaten = Snake::eat(&mut self.snakes, i, &mut self.rng, &mut self.food, &mut self.arena, self.bite_cooldown, self.food_contest);
+    if eaten.iter().any(|food| food.effect() == Effect::Split) {
+      Snake::split(&mut self.snakes, i, self.split_strategy, &mut self.rng, &mut self.arena);
+    }
+    Snake::pull_food(&self.snakes, i, &mut self.food);
+    if self.eat_fx {
+      self.particles.extend(eaten.iter().map(Particle::from));
+    }
+  }
+
+  // The rest of a dash past its first cell, eating along the way. The cell it stops on is eaten on the
+  // next move like any other
+  fn finish_dash(&mut self, i: usize, cells: u8) {
+    for _ in 1..cells {
+      if !self.snakes[i].is_alive() {
+        break;
+      }
+      self.eat(i);
+      if !Snake::step(&mut self.snakes, i, &mut self.rng, &mut self.arena) {
+        break;
+      }
+    }
+  }
+
+  // The longest snake past the target wins, ties go to whoever spawned first so the player wins them
+  fn check_target_length(&mut self) {
+    let Some(target) = self.target_length else {
+      return;
+    };
+    let winner = self
+      .snakes
+      .iter()
+      .map(Snake::len)
+      .enumerate()
+      .filter(|&(_, len)| len >= target)
+      .max_by(|(a, len_a), (b, len_b)| len_a.cmp(len_b).then(b.cmp(a)))
+      .map(|(i, _)| i);
+    if winner.is_some() {
+      self.finish(winner);
+    }
+  }
+
+  fn check_elimination(&mut self) {
+    if !self.tron {
+      return;
+    }
+    let mut alive = self.snakes.iter().enumerate().filter(|(_, snake)| snake.is_alive()).map(|(i, _)| i);
+    let (survivor, others) = (alive.next(), alive.next());
+    if others.is_none() && (survivor.is_none() || self.snakes.len() > 1) {
+      self.finish(survivor);
+    }
+  }
+
+  fn finish(&mut self, winner: Option<usize>) {
+    self.summary = GameSummary {
+      finished: true,
+      winner,
+      lengths: self.snakes.iter().map(Snake::len).collect(),
+      frames: self.frame_count,
+      elapsed: self.elapsed(),
+    };
+    self.running = self.restart_prompt;
+  }
+
+  // Food right next to a head is left alone so it can't vanish just as it's about to be eaten
+  fn expire_food(&mut self) {
+    for food in &mut self.food {
+      if food.is_rotten() {
+        if self.food_lifetime.is_none_or(|lifetime| food.is_decayed(lifetime)) {
+          food.relocate(&mut self.rng, &self.arena.size, &self.snakes);
+        }
+        continue;
+      }
+      let contested = self.snakes.iter().any(|snake| snake.head().quick_distance(food) <= 1);
+      if food.is_expired(self.food_lifetime) && !contested {
+        if self.rot {
+          food.rot();
+        } else {
+          food.relocate(&mut self.rng, &self.arena.size, &self.snakes);
+        }
+      }
+    }
+    self.arena.rotten.clear();
+    self.arena.rotten.extend(self.food.iter().filter(|food| food.is_rotten()).map(|food| food.position));
+  }
+
+  fn update_scroll(&mut self, frames: u32) {
+    let Some(rate) = self.endless else {
+      return;
+    };
+    self.scroll_frames += frames;
+    if self.scroll_frames < rate as u32 {
+      return;
+    }
+    self.scroll_frames = 0;
+    self.scrolled += 1;
 
-impl Game {
-  pub fn new() -> Self {
-    let termsize = readln::signals::term_size().unwrap().into();
-    Self {
-      termsize,
-      rng: Rng::new(),
-      top_halves: Vec::with_capacity(1 << 7),
-      bottom_halves: Vec::with_capacity(1 << 7),
-      arena: Arena::new(termsize.x / 4, termsize.y / 10, termsize.x / 2, termsize.y * 4 / 5),
-      delta: Instant::now(),
-      running: false,
-      paused: true,
-      frame_duration_us: TIME_US / 30,
-      debug: false,
-      frame: String::from(CLEAR),
+    // Everything moves a column left, segments already on the trailing column stay there until they move on
+    for i in 0..self.snakes.len() {
+      if self.snakes[i].is_alive() && self.snakes[i].head().x == 0 {
+        Snake::kill(&mut self.snakes, i, &mut self.rng, &mut self.arena);
+      }
+      self.snakes[i].body.iter_mut().for_each(|p| p.x = p.x.saturating_sub(1));
+    }
+    // Nothing else is on the leading column once everything moved, only food brought back there can clash
+    let column = Rect::new(self.arena.size.x - 1, 0, 1, self.arena.height());
+    let mut returned = Vec::new();
+    for food in &mut self.food {
+      if food.x == 0 {
+        food.relocate_in(&mut self.rng, &column, &self.snakes, &returned);
+        returned.push(food.position);
+      } else {
+        food.position.x -= 1;
+      }
+    }
+    self.arena.rotten.iter_mut().for_each(|p| p.x = p.x.saturating_sub(1));
+    if let Some(occupancy) = &mut self.arena.occupancy {
+      occupancy.invalidate();
     }
   }
 
-  pub fn fps(&mut self, fps: usize) -> &mut Self {
-    self.frame_duration_us = TIME_US / fps as u128;
-    self
+  fn update_demo(&mut self) {
+    if self.summary.finished {
+      self.reset();
+      self.followed_at = Instant::now();
+    }
+    if self.followed_at.elapsed() >= DEMO_FOLLOW {
+      let count = self.snakes.len();
+      self.follow = (1..=count).map(|i| (self.follow + i) % count).find(|&i| self.snakes[i].is_alive()).unwrap_or(0);
+      self.followed_at = Instant::now();
+    }
   }
 
-  pub fn run(&mut self) -> GameResult {
-    self.running = true;
-    let mut snakes: [Snake; 6] = [
-      Strategy::Player,
-      Strategy::Eat,
-      Strategy::Kill,
-      Strategy::Speed,
-      Strategy::Score,
-      Strategy::Cannibal,
-    ]
-    .map(|strat| {
-      let mut snake = Snake::random(8, strat, &mut self.rng, &self.arena.size);
-      if matches!(strat, Strategy::Player) {
-        snake.name = "You";
-      }
-      snake
+  fn update_ramp(&mut self, frames: u32) {
+    const MAX_LEVEL: u8 = 10;
+    let Some((interval, step)) = self.ramp else {
+      return;
+    };
+    self.ramp_ticks += frames;
+    if self.level >= MAX_LEVEL || Duration::from_micros(self.frame_duration_us as u64) * self.ramp_ticks < interval {
+      return;
+    }
+
+    self.ramp_ticks = 0;
+    self.level += 1;
+    for snake in self.snakes.iter_mut().skip(1) {
+      snake.ramp_up(step);
+      snake.strategy_params.kill_margin = snake.strategy_params.kill_margin.saturating_sub(1).max(2);
+    }
+  }
+
+  fn render(&mut self, delta: u128) -> fmt::Result {
+    self.arena.animate_resize(&mut self.food);
+    self.arena.fit(&self.termsize, &mut self.food);
+    self.arena.follow(self.snakes[self.follow].head());
+    self.particles.retain(|particle| !particle.is_expired());
+    self.layout()?;
+
+    // Both are lent out while the renderer reads the rest of the game
+    let mut frame = std::mem::take(&mut self.frame);
+    let mut renderer = self.renderer.take().expect("renderer is only taken while rendering");
+    frame.truncate(CLEAR.len());
+    let rendered = renderer.render(&GameView { game: self }, &mut frame);
+    self.frame = frame;
+    self.renderer = Some(renderer);
+    rendered?;
+
+    for particle in &mut self.particles {
+      particle.age();
+    }
+    self.frame_count = self.frame_count.wrapping_add(1);
+    self.frame_times.push(delta);
+    Ok(())
+  }
+
+  // Measures the scoreboard and status lines ahead of drawing so render_bounds doesn't depend on the renderer
+  fn layout(&mut self) -> fmt::Result {
+    self.scoreboard_end = self.scoreboard_entries().iter().fold(Point::new(0, 0), |end, (position, _, entry)| {
+      let entry_end = *position + ((entry.chars().count() as i32 - 1).max(0), 0);
+      Point::new(std::cmp::max(end.x, entry_end.x), std::cmp::max(end.y, entry_end.y))
     });
-    let mut food: [Food; 12] = std::array::from_fn(|i| Food::random(Effect::from(i), &mut self.rng, &self.arena.size));
+    let lines = self.stats().lines();
+    let width = lines.iter().map(|ln| ln.chars().count()).max().unwrap_or(0) as u8;
+    self.stats_origin = self.stats_layout(width, lines.len() as u8);
+    self.stats_end = self.stats_origin.map_or(Point::new(0, 0), |origin| origin + (width as i32 - 1, lines.len() as i32 - 1));
+    let (mut hint, mut status) = (String::new(), String::new());
+    self.hint_line(&mut hint, (0, 0))?;
+    self.status_line(&mut status)?;
+    self.ui_width = std::cmp::max(hint.chars().count(), status.chars().count()) as u16;
+    Ok(())
+  }
 
-    while self.running {
-      self.handle_input(&mut snakes[0], &mut food)?;
-      let delta = self.delta.elapsed().as_micros();
+  fn scoreboard_entries(&self) -> Vec<(Point, u8, String)> {
+    if matches!(self.scoreboard, Placement::Hidden) {
+      return Vec::new();
+    }
 
-      if readln::signals::term_resized(0) {
-        self.termsize = readln::signals::term_size().unwrap().into();
+    let mut scores: Vec<(u8, usize, String)> = self.snakes.iter().map(|snake| (snake.color, snake.score(), self.score_entry(snake))).collect();
+    scores.sort_by_key(|(_, score, _)| usize::MAX - *score);
+    let width = self.scoreboard_width();
+    let layout = self.scoreboard_layout(width, scores.len());
+    layout.into_iter().zip(scores).map(|(position, (color, _, entry))| (position, color, entry)).collect()
+  }
+
+  fn render_scoreboard(&self, f: &mut Frame) -> fmt::Result {
+    let entries = self.scoreboard_entries();
+    if entries.is_empty() {
+      return Ok(());
+    }
+    for (position, color, entry) in &entries {
+      mv(f, position)?;
+      fg(f, *color)?;
+      write!(f, "{entry}")?;
+    }
+    reset(f)
+  }
+
+  fn score_entry(&self, snake: &Snake) -> String {
+    match self.target_length {
+      Some(target) => format!("{}: {} ({}/{target})", snake.name, snake.score(), snake.len()),
+      None => format!("{}: {}", snake.name, snake.score()),
+    }
+  }
+
+  fn scoreboard_width(&self) -> u8 {
+    self.snakes.iter().map(|snake| self.score_entry(snake).chars().count()).max().unwrap_or(0) as u8
+  }
+
+  fn scoreboard_layout(&self, width: u8, count: usize) -> Vec<Point> {
+    let (position, size) = (&self.arena.position, &self.arena.window());
+    let max = self.termsize - Point::new(width, 0);
+    let clamp = |p: Point| Point::new(std::cmp::min(std::cmp::max(p.x, 1), max.x), std::cmp::min(std::cmp::max(p.y, 1), max.y));
+
+    match self.scoreboard {
+      Placement::Hidden => Vec::new(),
+      Placement::Right => (0..count).map(|i| clamp(*position + ((size.x + 2) as i32, 1 + i as i32))).collect(),
+      Placement::Left => (0..count).map(|i| clamp(*position + (-(width as i32) - 2, 1 + i as i32))).collect(),
+      Placement::Below => {
+        let column = width as i32 + 2;
+        let columns = std::cmp::max(1, (size.x as i32 + 2) / column);
+        (0..count as i32)
+          .map(|i| clamp(*position + ((i % columns) * column, size.y as i32 + 2 + i / columns)))
+          .collect()
       }
+    }
+  }
 
-      if !self.paused {
-        for i in 0..snakes.len() {
-          if snakes[i].can_move() {
-            if i != 0 {
-              let target = snakes[i].find_target(&snakes, &food);
-              Snake::seek(&mut snakes, i, &target, &self.arena.size);
-            }
-            Snake::eat(&mut snakes, i, &mut self.rng, &mut food, &self.arena);
-            Snake::serpentine(&mut snakes, i, &mut self.rng, &self.arena);
-          }
-        }
+  fn stats_layout(&self, width: u8, rows: u8) -> Option<Point> {
+    let (position, size) = (&self.arena.position, &self.arena.window());
+    let (x, y) = (position.x as i32, position.y as i32);
+    let (width, rows) = (width as i32, rows as i32);
+    let scoreboard_width = self.scoreboard_width() as i32 + 2;
+    let end = (self.scoreboard_end.x as i32, self.scoreboard_end.y as i32);
+
+    let spot = |placement: Placement| {
+      let shared = self.scoreboard == placement;
+      match placement {
+        Placement::Hidden => None,
+        Placement::Right if shared => Some((end.0 + 3, y + 1)),
+        Placement::Right => Some((x + size.x as i32 + 2, y + 1)),
+        Placement::Left if shared => Some((x - scoreboard_width - width - 2, y + 1)),
+        Placement::Left => Some((x - width - 2, y + 1)),
+        Placement::Below if shared => Some((x, end.1 + 2)),
+        Placement::Below => Some((x, y + size.y as i32 + 2)),
       }
+    };
+    let fits = |&(x, y): &(i32, i32)| x >= 1 && y >= 1 && x + width - 1 <= self.termsize.x as i32 && y + rows - 1 <= self.termsize.y as i32;
 
-      if delta >= self.frame_duration_us {
-        self.arena.render(&mut self.frame, &self.termsize, &mut food)?;
+    let stacked = match self.stats_pane {
+      Placement::Right if self.scoreboard == Placement::Right => Some((x + size.x as i32 + 2, end.1 + 2)),
+      Placement::Left if self.scoreboard == Placement::Left => Some((x - scoreboard_width, end.1 + 2)),
+      _ => None,
+    };
 
-        for snake in &snakes {
-          snake.render(&mut self.frame, &self.arena, &mut self.top_halves, &mut self.bottom_halves)?;
-        }
+    // Falls back to stacking under the scoreboard, then to the other sides when the requested spot runs off the terminal
+    let preferred = spot(self.stats_pane)?;
+    let (x, y) = std::iter::once(preferred)
+      .chain(stacked)
+      .chain([Placement::Right, Placement::Left, Placement::Below].into_iter().filter_map(spot))
+      .find(fits)
+      .unwrap_or(preferred);
+    let max = Point::new(self.termsize.x.saturating_sub(width as u8).max(1), self.termsize.y.saturating_sub(rows as u8).max(1));
+    let p = Point::new(0, 0) + (x, y);
+    Some(Point::new(p.x.clamp(1, max.x), p.y.clamp(1, max.y)))
+  }
 
-        for food in &food {
-          food.render(&mut self.frame, &self.arena.position)?;
-        }
+  fn stats(&self) -> Stats {
+    let alive = || self.snakes.iter().filter(|snake| snake.is_alive());
+    let fastest = alive().max_by(|a, b| a.cells_per_second().total_cmp(&b.cells_per_second()));
+    Stats {
+      food: self.food.len(),
+      alive: alive().count(),
+      snakes: self.snakes.len(),
+      fastest: fastest.map(|snake| (snake.name, snake.cells_per_second())),
+      longest: alive().max_by_key(|snake| snake.len()).map(|snake| (snake.name, snake.len())),
+      frame: self.frame_count,
+    }
+  }
+
+  fn render_name_tags(&self, f: &mut Frame) -> fmt::Result {
+    if !self.name_tags {
+      return Ok(());
+    }
+    let (position, window) = (self.arena.position, self.arena.window());
+    let heads: Vec<_> = self
+      .snakes
+      .iter()
+      .filter(|snake| snake.is_alive() && self.arena.is_visible(snake.head()))
+      .map(|snake| (snake, self.arena.screen(snake.head()), snake.name.chars().count() as u8))
+      .collect();
 
-        self.render_scoreboard(&snakes)?;
-        self.render_ui(&snakes[0])?;
-        println!("{}", self.frame);
-        self.top_halves.clear();
-        self.bottom_halves.clear();
-        self.frame.truncate(10);
-        self.delta = Instant::now() + Duration::from_micros((delta - self.frame_duration_us) as u64);
+    for (i, &(snake, head, len)) in heads.iter().enumerate() {
+      let crowded = heads.iter().enumerate().any(|(j, &(_, other, other_len))| {
+        i != j && head.y.abs_diff(other.y) <= 1 && head.x.abs_diff(other.x) <= (len + other_len) / 2
+      });
+      if crowded {
+        continue;
       }
+      let y = if head.y > position.y + 1 { head.y - 1 } else { head.y + 1 };
+      let left = position.x + 1;
+      let x = head.x.saturating_sub(len >> 1).clamp(left, std::cmp::max(left, (left + window.x).saturating_sub(len)));
+      mv(f, &Point::new(x, y))?;
+      fg(f, snake.color)?;
+      write!(f, "{}", snake.name)?;
     }
+    reset(f)
+  }
 
-    println!("\x1b[?25h");
+  fn render_stats(&self, f: &mut Frame, stats: &Stats) -> fmt::Result {
+    let Some(mut origin) = self.stats_origin else {
+      return Ok(());
+    };
+    for ln in stats.lines() {
+      mv(f, &origin)?;
+      write!(f, "{ln}")?;
+      origin.y += 1;
+    }
     Ok(())
   }
 
-  fn render_scoreboard(&mut self, snakes: &[Snake]) -> fmt::Result {
-    let mut scores: Box<[(u8, &str, usize)]> = snakes.iter().map(|snake| (snake.color, snake.name, snake.len())).collect();
-    scores.sort_by_key(|(_, _, score)| usize::MAX - *score);
-    let mut position = self.arena.position + ((self.arena.size.x + 2) as i32, 1);
-    for (color, name, score) in scores.iter() {
-      mv(&mut self.frame, &position)?;
-      fg(&mut self.frame, *color)?;
-      position.y += 1;
-      writeln!(&mut self.frame, "{name}: {score}")?;
-    }
-    reset(&mut self.frame)
-  }
-
-  fn handle_input(&mut self, player: &mut Snake, food: &mut [Food]) -> GameResult {
-    match readln::getch(0) {
-      Ok(b) => match b {
-        b'w' => player.steer(Direction::Up),
-        b'd' => player.steer(Direction::Right),
-        b's' => player.steer(Direction::Down),
-        b'a' => player.steer(Direction::Left),
-        66 => self.arena.position.y = self.arena.position.y.saturating_add(1),
-        65 => self.arena.position.y = self.arena.position.y.saturating_sub(1),
-        67 => self.arena.position.x = self.arena.position.x.saturating_add(1),
-        68 => self.arena.position.x = self.arena.position.x.saturating_sub(1),
-        b'k' => self.arena.size.y = self.arena.size.y.saturating_add(1),
-        b'j' => self.arena.shrink_height(1, food),
-        b'l' => self.arena.size.x = self.arena.size.x.saturating_add(1),
-        b'h' => self.arena.shrink_width(1, food),
+  fn handle_input(&mut self, b: u8) {
+    if self.demo {
+      if self.input.feed(b) == Some(Input::Key(b'q')) {
+        self.running = false;
+      }
+      return;
+    }
+    if self.summary.finished {
+      match self.input.feed(b) {
+        Some(Input::Key(b'r')) => self.reset(),
+        Some(Input::Key(b'q')) => self.running = false,
+        _ => (),
+      }
+      return;
+    }
+    let b = match self.input.feed(b) {
+      Some(Input::Key(b)) => b,
+      Some(Input::Arrow(dir)) => {
+        let (x, y) = dir.coords();
+        self.arena.position = self.arena.position + (x as i32, y as i32);
+        self.centered = false;
+        return;
+      }
+      Some(Input::Mouse(mouse)) if self.mouse => return self.handle_mouse(mouse),
+      Some(Input::Focus(focused)) if self.pause_on_blur => {
+        if !focused && !self.paused {
+          (self.paused, self.blurred) = (true, true);
+        } else if focused && self.blurred {
+          (self.paused, self.blurred) = (false, false);
+        }
+        return;
+      }
+      _ => return,
+    };
+
+    let steering = (0..self.players).find_map(|player| Some((player, Direction::from_key(&self.player_keys[player], b)?)));
+    if let Some((player, dir)) = steering {
+      self.steer(player, dir);
+    } else if b == self.dash_key {
+      self.snakes[0].dash();
+    } else {
+      match b {
+        b'k' if self.animate_resize => self.resize_arena((0, 1)),
+        b'j' if self.animate_resize => self.resize_arena((0, -1)),
+        b'l' if self.animate_resize => self.resize_arena((1, 0)),
+        b'h' if self.animate_resize => self.resize_arena((-1, 0)),
+        b'k' => {
+          self.arena.grow_height(1);
+          self.keep_aspect(false);
+        }
+        b'j' => {
+          self.arena.shrink_height(1, &mut self.food);
+          self.keep_aspect(false);
+        }
+        b'l' => {
+          self.arena.size.x = self.arena.size.x.saturating_add(1);
+          self.keep_aspect(true);
+        }
+        b'h' => {
+          self.arena.shrink_width(1, &mut self.food);
+          self.keep_aspect(true);
+        }
+        b'c' => self.follow = (self.follow + 1) % self.snakes.len(),
         b'f' => self.debug = !self.debug,
-        b'p' => self.paused = !self.paused,
+        b'g' if self.debug => {
+          self.god_mode(!self.god_mode);
+        }
+        b'p' => (self.paused, self.blurred) = (!self.paused, false),
         b'q' => self.running = false,
         _ => (),
-      },
-      Err(err) if err.kind() == io::ErrorKind::WouldBlock => (),
-      Err(err) => return Err(GameError::Io(err)),
+      }
     }
+  }
 
-    Ok(())
+  fn handle_mouse(&mut self, mouse: Mouse) {
+    const LEFT_BUTTON: u16 = 0;
+    if mouse.button != LEFT_BUTTON {
+      return;
+    }
+
+    let Point { x, y } = self.arena.position;
+    let window = self.arena.window();
+    let (right, bottom) = (x + window.x + 1, y + window.y + 1);
+    let p = mouse.position;
+
+    if mouse.pressed {
+      let on_border =
+        ((p.x == x || p.x == right) && (y..=bottom).contains(&p.y)) || ((p.y == y || p.y == bottom) && (x..=right).contains(&p.x));
+      self.drag = if p.x == right && p.y == bottom {
+        Some((Drag::Resize, p))
+      } else if on_border {
+        Some((Drag::Move, p))
+      } else {
+        None
+      };
+    } else if let Some((drag, from)) = self.drag.take() {
+      let delta = (p.x as i32 - from.x as i32, p.y as i32 - from.y as i32);
+      match drag {
+        Drag::Move => {
+          self.arena.position = self.arena.position + delta;
+          self.centered = false;
+        }
+        Drag::Resize => {
+          let scale = self.arena.scale;
+          self.arena.resize_by((delta.0 / scale.x as i32, delta.1 / scale.y as i32), &mut self.food);
+        }
+      }
+    }
   }
 
-  fn render_ui(&mut self, player: &Snake) -> fmt::Result {
-    mv(&mut self.frame, &(self.arena.position + (0, -2)))?;
-    if self.debug {
-      let fps = TIME_US / self.delta.elapsed().as_micros();
-      write!(
-        &mut self.frame,
-        "{fps} FPS | T: {:03} | B: {:03}",
-        self.top_halves.len(),
-        self.bottom_halves.len(),
-      )?;
+  fn steer(&mut self, player: usize, dir: Direction) {
+    if self.paused {
+      self.snakes[player].aim(dir);
     } else {
-      write!(&mut self.frame, "Press F for Debug information")?;
+      self.snakes[player].steer(dir);
     }
+    self.stepped = true;
+  }
+
+  fn resize_arena(&mut self, (x, y): (i32, i32)) {
+    const RESIZE_STEP: i32 = 4;
+    const RESIZE_FRAMES: u8 = 6;
+    let mut target = self.arena.resize_target() + (x * RESIZE_STEP, y * RESIZE_STEP);
+    if let Some(ratio) = self.aspect {
+      target = Arena::aspect_size(&target, ratio, x != 0);
+    }
+    self.arena.resize_to(target, RESIZE_FRAMES);
+  }
+
+  fn keep_aspect(&mut self, width_changed: bool) {
+    let Some(ratio) = self.aspect else {
+      return;
+    };
+    let size = Arena::aspect_size(&self.arena.size, ratio, width_changed);
+    let diff = (size.x as i32 - self.arena.size.x as i32, size.y as i32 - self.arena.size.y as i32);
+    self.arena.resize_by(diff, &mut self.food);
+  }
+
+  // Lays out the controls within the arena width, returning how many rows they took
+  fn render_legend(&self, f: &mut Frame, origin: Point) -> Result<u8, fmt::Error> {
+    let width = self.arena.window().x as usize;
+    let rows = match self.legend {
+      Legend::SingleColumn | Legend::TwoColumns => {
+        let columns = if matches!(self.legend, Legend::TwoColumns) { 2 } else { 1 };
+        let rows = self.controls.len().div_ceil(columns);
+        let widest = self.controls.iter().map(|c| visible_len(c)).max().unwrap_or(0);
+        let column_width = std::cmp::min(widest + 2, width / columns);
+        for (i, c) in self.controls.iter().enumerate() {
+          let c = self.arena.charset.text(c);
+          mv(f, &(origin + ((i / rows * column_width) as i32, (i % rows) as i32)))?;
+          write!(f, "{}", truncate_visible(&c, column_width.saturating_sub(1)).0)?;
+          reset(f)?;
+        }
+        rows
+      }
+      Legend::Compact => {
+        let (mut x, mut y) = (0, 0);
+        for c in &self.controls {
+          let c = self.arena.charset.text(c).replacen(" -> ", " ", 1);
+          let (c, len) = truncate_visible(&c, width);
+          if x > 0 && x + len > width {
+            (x, y) = (0, y + 1);
+          }
+          mv(f, &(origin + (x as i32, y as i32)))?;
+          write!(f, "{c}")?;
+          reset(f)?;
+          x += len + 2;
+        }
+        y + 1
+      }
+    };
+    Ok(rows as u8)
+  }
+
+  fn render_ui(&self, f: &mut Frame, halves: (usize, usize)) -> fmt::Result {
+    mv(f, &(self.arena.position + (0, -2)))?;
+    self.hint_line(f, halves)?;
 
     if self.paused {
-      let mut center = self.arena.position + ((self.arena.size.x as i32 / 2) - 22, 0);
-      fg(&mut self.frame, 84)?;
-      for ln in LOGO.lines() {
-        mv(&mut self.frame, &center)?;
-        write!(&mut self.frame, "{}", ln)?;
+      let logo_width = self.logo.lines().map(|ln| ln.chars().count()).max().unwrap_or(0) as i32;
+      let mut center = self.arena.position + ((self.arena.window().x as i32 - logo_width) / 2, 0);
+      fg(f, 84)?;
+      for ln in self.logo.lines() {
+        mv(f, &center)?;
+        write!(f, "{}", self.arena.charset.text(ln))?;
         center.y += 1;
       }
       let first_y = center.y + 1;
-      mv(&mut self.frame, &center)?;
-      reset(&mut self.frame)?;
-      write!(&mut self.frame, "\x1b[1mControls \x1b[5m(Press P to Play)\x1b[0m")?;
-      center.x = self.arena.position.x + 1;
-      for (i, c) in CONTROLS.iter().enumerate() {
-        center.y += 1;
-        if i != 0 && i % 8 == 0 {
-          center.y = first_y;
-          center.x = center.x.saturating_add(32);
+      mv(f, &center)?;
+      reset(f)?;
+      write!(f, "\x1b[1mControls \x1b[5m(Press P to Play)\x1b[0m")?;
+      let rows = self.render_legend(f, Point::new(self.arena.position.x + 1, first_y))?;
+
+      if !self.records.is_empty() {
+        center = Point::new(self.arena.position.x + 1, first_y + rows + 1);
+        mv(f, &center)?;
+        write!(f, "\x1b[1mRecords\x1b[0m")?;
+        for (name, score) in self.records.iter() {
+          center.y += 1;
+          mv(f, &center)?;
+          write!(f, "{name}: {score}")?;
         }
-        mv(&mut self.frame, &center)?;
-        write!(&mut self.frame, "{c}")?;
       }
     }
 
-    mv(&mut self.frame, &(self.arena.position + (0, -1)))?;
+    if self.summary.finished && self.restart_prompt {
+      self.render_game_over(f)?;
+    } else if !self.paused {
+      self.render_death_banner(f)?;
+    }
+
+    let warnings: Vec<&str> = [(self.is_low_contrast(), "LOW CONTRAST"), (self.is_low_fps(), "LOW FPS")]
+      .into_iter()
+      .filter_map(|(shown, warning)| shown.then_some(warning))
+      .collect();
+    if !warnings.is_empty() {
+      let warning = warnings.join(" ");
+      mv(f, &(self.arena.position + (self.arena.window().x as i32 - warning.len() as i32 + 2, -2)))?;
+      fg(f, 196)?;
+      write!(f, "\x1b[1m{warning}")?;
+      reset(f)?;
+    }
+
+    mv(f, &(self.arena.position + (0, -1)))?;
+    self.status_line(f)
+  }
+
+  fn render_death_banner(&self, f: &mut Frame) -> fmt::Result {
+    if self.demo || self.died_at.is_none_or(|at| at.elapsed() >= self.death_banner) {
+      return Ok(());
+    }
+    let lines: &[&str] = if self.tron { &["Eliminated."] } else { &["GAME OVER", "You died! Respawning..."] };
+    self.render_banner(f, lines, 196)
+  }
+
+  fn render_game_over(&self, f: &mut Frame) -> fmt::Result {
+    if self.demo {
+      return Ok(());
+    }
+    let result = match self.summary.winner {
+      Some(winner) => format!("{} WINS", self.snakes[winner].name),
+      None => "DRAW".to_string(),
+    };
+    self.render_banner(f, &["GAME OVER", &result, "Press R to restart, Q to quit."], 226)
+  }
+
+  // Bold lines centered over the arena
+  fn render_banner(&self, f: &mut Frame, lines: &[&str], color: u8) -> fmt::Result {
+    let window = self.arena.window();
+    let top = self.arena.position.y + (window.y.saturating_sub(lines.len() as u8)) / 2 + 1;
+    fg(f, color)?;
+    write!(f, "\x1b[1m")?;
+    for (i, ln) in lines.iter().enumerate() {
+      let x = self.arena.position.x + (window.x.saturating_sub(ln.len() as u8)) / 2 + 1;
+      mv(f, &Point::new(x, top + i as u8))?;
+      write!(f, "{ln}")?;
+    }
+    reset(f)
+  }
+
+  fn is_low_fps(&self) -> bool {
+    let frame = Duration::from_micros(self.frame_duration_us as u64);
+    self.low_fps.is_some_and(|ratio| self.frame_times.is_slow(frame, ratio))
+  }
+
+  fn is_low_contrast(&self) -> bool {
+    self.snakes.iter().any(|snake| contrast(snake.color, self.background) < MIN_CONTRAST)
+  }
+
+  fn hint_line(&self, f: &mut String, (top, bottom): (usize, usize)) -> fmt::Result {
+    if !self.debug {
+      return write!(f, "Press F for Debug information");
+    }
+    let fps = TIME_US / self.delta.elapsed().as_micros().max(1);
+    write!(f, "{fps} FPS | T: {top:03} | B: {bottom:03}")?;
+    self.frame_times.sparkline(f, self.arena.charset)
+  }
+
+  fn status_line(&self, f: &mut String) -> fmt::Result {
+    let player = &self.snakes[0];
+    write!(f, "SPEED: {:.1} CELLS/S ", player.cells_per_second())?;
+    let mut meter = String::new();
+    player.boost_meter(&mut meter, 8)?;
+    write!(f, "{}", self.arena.charset.text(&meter))?;
     write!(
-      &mut self.frame,
-      "SPEED: {}/255 | SCORE: {} | COORDS: {:03}:{:03} | ARENA SIZE: {:03}:{:03}",
-      player.speed(),
-      player.len(),
+      f,
+      " | SCORE: {} | COORDS: {:03}:{:03} | ARENA SIZE: {:03}:{:03}",
+      player.score(),
       player.head().x,
       player.head().y,
       self.arena.size.x,
       self.arena.size.y,
-    )
+    )?;
+    if self.dash.charges > 0 {
+      write!(f, " | DASH: {}/{}", player.dash_charges(), self.dash.charges)?;
+    }
+    if self.ramp.is_some() {
+      write!(f, " | LEVEL: {}", self.level)?;
+    }
+    match (self.summary.winner, self.target_length) {
+      (Some(winner), _) => write!(f, " | WINNER: {}", self.snakes[winner].name)?,
+      (None, Some(target)) => write!(f, " | FIRST TO {target}")?,
+      (None, None) => (),
+    }
+    Ok(())
+  }
+}
+
+const FRAME_SAMPLES: usize = 120;
+const FRAME_BUCKET: usize = 4;
+// Frames averaged to decide whether the game keeps up, long enough that a single hitch doesn't count
+const SLOW_SAMPLES: usize = 60;
+
+// The time between the most recent rendered frames in microseconds
+pub struct FrameTimes {
+  samples: [u32; FRAME_SAMPLES],
+  idx: usize,
+  len: usize,
+}
+
+impl Default for FrameTimes {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl FrameTimes {
+  pub fn new() -> Self {
+    Self {
+      samples: [0; FRAME_SAMPLES],
+      idx: 0,
+      len: 0,
+    }
+  }
+
+  pub fn push(&mut self, us: u128) {
+    self.samples[self.idx] = us.min(u32::MAX as u128) as u32;
+    self.idx = (self.idx + 1) % FRAME_SAMPLES;
+    self.len = std::cmp::min(self.len + 1, FRAME_SAMPLES);
+  }
+
+  // Whether the recent frames average below `ratio` of the fps a frame of `frame` gives
+  pub fn is_slow(&self, frame: Duration, ratio: f32) -> bool {
+    if self.len < SLOW_SAMPLES {
+      return false;
+    }
+    let recent = (1..=SLOW_SAMPLES).map(|i| self.samples[(self.idx + FRAME_SAMPLES - i) % FRAME_SAMPLES] as u64);
+    let average = recent.sum::<u64>() as f32 / SLOW_SAMPLES as f32;
+    average * ratio > frame.as_micros() as f32
+  }
+
+  fn sparkline(&self, f: &mut String, charset: CharSet) -> fmt::Result {
+    const UNICODE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    const ASCII_BARS: [char; 8] = ['_', '.', ':', '-', '=', '+', '*', '#'];
+    let bars = if charset == CharSet::Ascii { ASCII_BARS } else { UNICODE_BARS };
+    // Oldest samples first, each bucket keeps its slowest frame so hitches stand out
+    let ordered: Box<[u32]> = self.samples[self.idx..].iter().chain(&self.samples[..self.idx]).copied().collect();
+    let buckets: Box<[u32]> = ordered.chunks(FRAME_BUCKET).map(|c| *c.iter().max().unwrap()).collect();
+    let max = buckets.iter().copied().max().unwrap_or(0).max(1);
+    write!(f, " | ")?;
+    for bucket in buckets.iter() {
+      f.push(bars[(*bucket as usize * (bars.len() - 1)) / max as usize]);
+    }
+    Ok(())
+  }
+}
+
+impl Default for Game {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+// Draws a frame from a read-only view of the game, the frame already starts with the screen clear
+pub trait Renderer {
+  fn render(&mut self, view: &GameView, out: &mut Frame) -> fmt::Result;
+}
+
+pub struct GameView<'a> {
+  game: &'a Game,
+}
+
+impl GameView<'_> {
+  pub fn snakes(&self) -> &[Snake] {
+    &self.game.snakes
+  }
+
+  pub fn food(&self) -> &[Food] {
+    &self.game.food
+  }
+
+  pub fn particles(&self) -> &[Particle] {
+    &self.game.particles
+  }
+
+  pub fn arena_position(&self) -> Point {
+    self.game.arena.position
+  }
+
+  pub fn arena_size(&self) -> Point {
+    self.game.arena.size
+  }
+
+  pub fn camera(&self) -> Point {
+    self.game.arena.camera
+  }
+
+  pub fn charset(&self) -> CharSet {
+    self.game.arena.charset
+  }
+
+  pub fn paused(&self) -> bool {
+    self.game.paused
+  }
+
+  pub fn level(&self) -> u8 {
+    self.game.level
+  }
+
+  pub fn frame_count(&self) -> usize {
+    self.game.frame_count
+  }
+
+  pub fn elapsed(&self) -> Duration {
+    self.game.elapsed()
+  }
+
+  pub fn summary(&self) -> &GameSummary {
+    &self.game.summary
+  }
+
+  pub fn low_fps(&self) -> bool {
+    self.game.is_low_fps()
+  }
+
+  pub fn low_contrast(&self) -> bool {
+    self.game.is_low_contrast()
+  }
+
+  pub fn stats(&self) -> Stats {
+    self.game.stats()
+  }
+
+  pub fn records(&self) -> &Records {
+    &self.game.records
+  }
+}
+
+// The built-in terminal renderer, pairing up half block cells as it draws the snakes
+pub struct TerminalRenderer {
+  top_halves: Vec<ColoredPoint>,
+  bottom_halves: Vec<ColoredPoint>,
+}
+
+impl Default for TerminalRenderer {
+  fn default() -> Self {
+    Self {
+      top_halves: Vec::with_capacity(1 << 7),
+      bottom_halves: Vec::with_capacity(1 << 7),
+    }
+  }
+}
+
+impl Renderer for TerminalRenderer {
+  fn render(&mut self, view: &GameView, out: &mut Frame) -> fmt::Result {
+    let game = view.game;
+    self.top_halves.clear();
+    self.bottom_halves.clear();
+    game.arena.render(out)?;
+
+    for particle in &game.particles {
+      particle.render(out, &game.arena)?;
+    }
+
+    for snake in &game.snakes {
+      snake.render(out, &game.arena, &mut self.top_halves, &mut self.bottom_halves, &game.cannibal_style, game.frame_count)?;
+    }
+
+    let too_short = game.snakes.get(game.follow).is_some_and(|snake| !snake.can_turn_cannibal());
+    for food in &game.food {
+      if too_short && food.effect() == Effect::Cannibal {
+        food.render_inert(out, &game.arena, game.food_lifetime)?;
+      } else {
+        food.render(out, &game.arena, game.food_lifetime)?;
+      }
+    }
+
+    game.render_name_tags(out)?;
+    game.render_scoreboard(out)?;
+    game.render_stats(out, &view.stats())?;
+    game.render_ui(out, (self.top_halves.len(), self.bottom_halves.len()))
+  }
+}
+
+#[derive(Clone, Copy)]
+enum Drag {
+  Move,
+  Resize,
+}
+
+// Snakes on the same team pass through each other and never bite or reward kills among themselves.
+// Assigned ids follow spawn order starting with the player, snakes past the end get a team of their own.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Teams {
+  Solo,
+  ByStrategy,
+  Assigned(Vec<u8>),
+}
+
+// What a player's snake does until it's first steered, either keep its spawn direction, wait in place
+// or follow an AI strategy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Idle {
+  Moving,
+  Still,
+  Autopilot(Strategy),
+}
+
+// How a finished game ended, the winner is an index into `Game::snakes`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameSummary {
+  pub finished: bool,
+  pub winner: Option<usize>,
+  pub lengths: Vec<usize>,
+  pub frames: usize,
+  pub elapsed: Duration,
+}
+
+// Totals shown by the stats pane, fastest and longest only count snakes that are alive
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+  pub food: usize,
+  pub alive: usize,
+  pub snakes: usize,
+  pub fastest: Option<(&'static str, f32)>,
+  pub longest: Option<(&'static str, usize)>,
+  pub frame: usize,
+}
+
+impl Stats {
+  pub fn lines(&self) -> Vec<String> {
+    let mut lines = vec![format!("FOOD: {}", self.food), format!("ALIVE: {}/{}", self.alive, self.snakes)];
+    if let Some((name, speed)) = self.fastest {
+      lines.push(format!("FASTEST: {name} {speed:.1} CELLS/S"));
+    }
+    if let Some((name, len)) = self.longest {
+      lines.push(format!("LONGEST: {name} {len}"));
+    }
+    lines.push(format!("FRAME: {}", self.frame));
+    lines
   }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Legend {
+  SingleColumn,
+  TwoColumns,
+  Compact,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Placement {
+  Right,
+  Left,
+  Below,
+  Hidden,
+}
+
 pub type GameResult<T = ()> = Result<T, GameError>;
 
 #[derive(Debug)]
 pub enum GameError {
   Io(io::Error),
   Fmt(fmt::Error),
+  KeyConflict(u8),
+  OutOfBounds(Point),
+  Occupied(Point),
+  Config(ConfigError),
+}
+
+// Settings the fallible builders turn down, the plain builders clamp these to the nearest valid value instead
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigError {
+  Fps(usize),
+  ArenaSize(Point),
+  Inset(u8),
+  AspectRatio(f32),
+}
+
+impl Display for ConfigError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Fps(fps) => write!(f, "{fps} fps is outside 1..={MAX_FPS}"),
+      Self::ArenaSize(size) => write!(f, "a {}x{} arena is outside {MIN_ARENA}x{MIN_ARENA}..={}x{MAX_HEIGHT}", size.x, size.y, u8::MAX),
+      Self::Inset(inset) => write!(f, "an inset of {inset} leaves less than {MIN_ARENA} cells to play in"),
+      Self::AspectRatio(ratio) => write!(f, "{ratio} is not a positive aspect ratio"),
+    }
+  }
 }
 
 impl std::error::Error for GameError {}
@@ -229,12 +2168,17 @@ macro_rules! from_err {
 
 from_err!(Io, io::Error);
 from_err!(Fmt, fmt::Error);
+from_err!(Config, ConfigError);
 
 impl Display for GameError {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
       Self::Io(err) => write!(f, "{err}"),
       Self::Fmt(err) => write!(f, "{err}"),
+      Self::KeyConflict(key) => write!(f, "{:?} is bound to more than one action", *key as char),
+      Self::OutOfBounds(p) => write!(f, "{},{} is outside the arena", p.x, p.y),
+      Self::Occupied(p) => write!(f, "{},{} is already occupied", p.x, p.y),
+      Self::Config(err) => write!(f, "{err}"),
     }
   }
 }
@@ -251,20 +2195,23 @@ const LOGO: &str = r#"
       ░           ░       ░  ░░  ░      ░  ░
 "#;
 
-const CONTROLS: [&str; 15] = [
+const CONTROLS: [&str; 18] = [
   "\x1b[1mP\x1b[0m -> Play / Pause",
   "\x1b[1mQ\x1b[0m -> Quit",
   "\x1b[1mW\x1b[0m -> Move Up",
   "\x1b[1mD\x1b[0m -> Move Right",
   "\x1b[1mS\x1b[0m -> Move Down",
   "\x1b[1mA\x1b[0m -> Move Left",
+  "\x1b[1mSpace\x1b[0m -> Dash",
   "\x1b[1mK\x1b[0m -> Increase Arena Height",
   "\x1b[1mJ\x1b[0m -> Decrease Arena Height",
   "\x1b[1mL\x1b[0m -> Increase Arena Width",
   "\x1b[1mH\x1b[0m -> Decrease Arena Width",
   "\x1b[1mF\x1b[0m -> Show Debug Info",
-  "\x1b[1m\x1b[0m -> Move Arena Down",
-  "\x1b[1m\x1b[0m -> Move Arena Up",
-  "\x1b[1m\x1b[0m -> Move Arena Right",
-  "\x1b[1m\x1b[0m -> Move Arena Left",
+  "\x1b[1mG\x1b[0m -> God Mode (Debug)",
+  "\x1b[1mC\x1b[0m -> Cycle Camera Target",
+  "\x1b[1m↓\x1b[0m -> Move Arena Down",
+  "\x1b[1m↑\x1b[0m -> Move Arena Up",
+  "\x1b[1m→\x1b[0m -> Move Arena Right",
+  "\x1b[1m←\x1b[0m -> Move Arena Left",
 ];
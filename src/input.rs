@@ -0,0 +1,91 @@
+use crate::math::{Direction, Point};
+
+const ESC: u8 = 0x1b;
+const MAX_SEQUENCE: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Input {
+  Key(u8),
+  Arrow(Direction),
+  Mouse(Mouse),
+  // Focus reports are `ESC [ I` when the terminal gains focus and `ESC [ O` when it loses it
+  Focus(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mouse {
+  pub button: u16,
+  pub position: Point,
+  pub pressed: bool,
+}
+
+#[derive(Default)]
+pub struct InputParser {
+  sequence: Vec<u8>,
+}
+
+impl InputParser {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn feed(&mut self, b: u8) -> Option<Input> {
+    if self.sequence.is_empty() {
+      if b == ESC {
+        self.sequence.push(b);
+        return None;
+      }
+      return Some(Input::Key(b));
+    }
+
+    self.sequence.push(b);
+    if self.sequence.len() == 2 {
+      if b == b'[' {
+        return None;
+      }
+      self.sequence.clear();
+      return Some(Input::Key(b));
+    }
+
+    // CSI sequences end with a byte in the 0x40..=0x7E range, SGR mouse reports are `ESC [ < b ; x ; y M|m`
+    if !(0x40..=0x7e).contains(&b) {
+      if self.sequence.len() > MAX_SEQUENCE {
+        self.sequence.clear();
+      }
+      return None;
+    }
+
+    let input = match &self.sequence[2..] {
+      [b'<', params @ ..] => parse_sgr_mouse(params).map(Input::Mouse),
+      [b'I'] => Some(Input::Focus(true)),
+      [b'O'] => Some(Input::Focus(false)),
+      [b] => Direction::from_arrow(*b).map(Input::Arrow),
+      _ => None,
+    };
+    self.sequence.clear();
+    input
+  }
+}
+
+pub fn parse_sgr_mouse(params: &[u8]) -> Option<Mouse> {
+  let (&last, params) = params.split_last()?;
+  let pressed = match last {
+    b'M' => true,
+    b'm' => false,
+    _ => return None,
+  };
+
+  let mut fields = std::str::from_utf8(params).ok()?.split(';').map(|n| n.parse::<u16>().ok());
+  let button = fields.next()??;
+  let x = fields.next()??;
+  let y = fields.next()??;
+  if fields.next().is_some() {
+    return None;
+  }
+
+  Some(Mouse {
+    button,
+    position: Point::new(x.min(u8::MAX as u16) as u8, y.min(u8::MAX as u16) as u8),
+    pressed,
+  })
+}
@@ -0,0 +1,36 @@
+//! Snake in terminal
+//!
+//! ```no_run
+//! use snakers::{Game, GameResult};
+//!
+//! fn main() -> GameResult {
+//!   Game::new().fps(60).run()
+//! }
+//! ```
+
+mod charset;
+mod consts;
+mod esc;
+mod game;
+mod input;
+mod map;
+mod math;
+mod output;
+mod preferences;
+mod records;
+mod results;
+mod snake;
+mod tournament;
+
+pub use charset::CharSet;
+pub use esc::{contrast, Frame, MIN_CONTRAST};
+pub use game::{ConfigError, FrameTimes, Game, GameError, GameResult, GameSummary, GameView, Idle, Legend, Placement, Renderer, Stats, Teams, TerminalRenderer};
+pub use input::{parse_sgr_mouse, Input, InputParser, Mouse};
+pub use map::{Effect, Food, FoodEffect, Particle, Strategy, WallPolicy, PARTICLE_FRAMES};
+pub use math::{Direction, Point, Rect};
+pub use output::{FlushPolicy, FrameClock, FrameWriter, RestoreOnExit, RestoreOnPanic};
+pub use preferences::Preferences;
+pub use records::Records;
+pub use results::Format;
+pub use snake::{CannibalStyle, Dash, DeathShrink, FoodContest, KillPolicy, OtherCollision, PackPlan, ScoreWeights, SelfCollision, Snake, SnakeStats, StackRule, Stacking, StrategyParams};
+pub use tournament::{StrategyReport, Tournament, TournamentReport};
@@ -1,12 +1,12 @@
-mod consts;
-mod esc;
-mod game;
-mod map;
-mod math;
-mod snake;
-
-use game::{Game, GameResult};
+use snakers::{Game, GameResult};
+use std::path::Path;
 
 fn main() -> GameResult {
-  Game::new().fps(60).run()
+  let mut game = Game::new();
+  // Records and preferences only live in the home directory for the binary, Game::new never reads them
+  if let Some(home) = std::env::var_os("HOME") {
+    game.records_file(Path::new(&home).join(".snakers_records"));
+    game.with_preferences(Path::new(&home).join(".snakers_prefs"));
+  }
+  game.fps(60).run()
 }
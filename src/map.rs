@@ -1,40 +1,220 @@
-use crate::esc::{fg, reset};
-use crate::math::{Point, Rng};
+use crate::charset::CharSet;
+use crate::esc::{fg, reset, Frame};
+use crate::math::{Point, Rect, Rng};
 use crate::snake::Snake;
 use std::fmt::Write;
-use std::{fmt, ops::Deref, time::Instant};
+use std::{
+  fmt,
+  ops::Deref,
+  rc::Rc,
+  time::{Duration, Instant},
+};
+
+// Each terminal row holds two logical rows, so the height is capped to keep the logical height
+// within a u8 while leaving u8::MAX free to detect moving past the top edge
+pub const MAX_HEIGHT: u8 = 127;
+// Smallest width and height resizing goes down to
+pub const MIN_ARENA: u8 = 8;
 
 pub struct Arena {
   pub position: Point,
   pub size: Point,
+  pub camera: Point,
+  pub wrap_x: bool,
+  pub wrap_y: bool,
+  pub grid: Option<u8>,
+  pub wall_policy: WallPolicy,
+  pub charset: CharSet,
+  pub viewport: bool,
+  pub inset: u8,
+  pub rotten: Vec<Point>,
+  pub occupancy: Option<Occupancy>,
+  // Terminal columns and rows drawn for every logical cell
+  pub scale: Point,
+  view: Point,
+  resize: Option<(Point, u8)>,
 }
 
 impl Arena {
   pub fn new(x: u8, y: u8, w: u8, h: u8) -> Self {
     Self {
       position: Point::new(x, y),
-      size: Point::new(w, h),
+      size: Point::new(w, std::cmp::min(h, MAX_HEIGHT)),
+      camera: Point::new(0, 0),
+      wrap_x: true,
+      wrap_y: true,
+      grid: None,
+      wall_policy: WallPolicy::Kill,
+      charset: CharSet::Unicode,
+      viewport: false,
+      inset: 0,
+      rotten: Vec::new(),
+      occupancy: None,
+      scale: Point::new(1, 1),
+      view: Point::new(w, std::cmp::min(h, MAX_HEIGHT) << 1),
+      resize: None,
+    }
+  }
+
+  // 120 columns by 60 rows (120 logical rows) at 2:3, kept fixed so benchmark runs stay comparable
+  pub fn benchmark() -> Self {
+    Self::new(2, 3, 120, 60)
+  }
+
+  // Brings the occupancy grid, when there is one, up to date with a snake whose body just changed
+  pub fn sync(&mut self, snakes: &[Snake], idx: usize) {
+    if let Some(occupancy) = &mut self.occupancy {
+      occupancy.sync(snakes, idx);
+    }
+  }
+
+  pub fn resize_to(&mut self, target: Point, frames: u8) {
+    let target = Point::new(std::cmp::max(MIN_ARENA, target.x), target.y.clamp(MIN_ARENA, MAX_HEIGHT));
+    self.resize = Some((target, frames.max(1)));
+  }
+
+  pub fn resize_target(&self) -> Point {
+    self.resize.map_or(self.size, |(target, _)| target)
+  }
+
+  pub fn animate_resize(&mut self, food: &mut [Food]) {
+    let Some((target, frames)) = self.resize.take() else {
+      return;
+    };
+
+    let step = |from: u8, to: u8| (from as i32 + (to as i32 - from as i32) / frames as i32) as u8;
+    let next = Point::new(step(self.size.x, target.x), step(self.size.y, target.y));
+
+    if next.x < self.size.x {
+      self.shrink_width(self.size.x - next.x, food);
+    } else {
+      self.size.x = next.x;
+    }
+    if next.y < self.size.y {
+      self.shrink_height(self.size.y - next.y, food);
+    } else {
+      self.size.y = next.y;
+    }
+
+    if frames > 1 {
+      self.resize = Some((target, frames - 1));
+    }
+  }
+
+  pub fn height(&self) -> u8 {
+    self.size.y << 1
+  }
+
+  pub fn hits_wall(&self, p: &Point) -> bool {
+    (!self.wrap_x && p.x >= self.size.x) || (!self.wrap_y && p.y >= self.height())
+  }
+
+  fn view(&self, termsize: &Point) -> Point {
+    let frame = self.inset << 1;
+    Point::new(
+      std::cmp::min(self.size.x, termsize.x.saturating_sub(self.position.x + 16 + frame) / self.scale.x),
+      std::cmp::min(self.size.y, termsize.y.saturating_sub(self.position.y + 2 + frame) / self.scale.y) << 1,
+    )
+  }
+
+  // Terminal cells inside the border, the visible window in viewport mode or else the whole arena,
+  // plus the inset frame on every side
+  pub fn window(&self) -> Point {
+    let inner = if self.viewport { Point::new(self.view.x, self.view.y >> 1) } else { self.size };
+    Point::new(inner.x.saturating_mul(self.scale.x) + (self.inset << 1), inner.y.saturating_mul(self.scale.y) + (self.inset << 1))
+  }
+
+  // Moves the playable area in from the border by `inset` cells, keeping the drawn border where it was
+  pub fn set_inset(&mut self, inset: u8, food: &mut [Food]) {
+    let frame = |inset: u8| inset.saturating_mul(2);
+    self.size.x = self.size.x.saturating_add(frame(self.inset));
+    self.grow_height(frame(self.inset));
+    self.shrink_width(frame(inset), food);
+    self.shrink_height(frame(inset), food);
+    self.inset = inset;
+  }
+
+  pub fn follow(&mut self, target: &Point) {
+    let max = Point::new(self.size.x, self.height()) - self.view;
+    self.camera.x = std::cmp::min(target.x.saturating_sub(self.view.x >> 1), max.x);
+    // Keep the camera on an even row so top/bottom halves stay aligned with the terminal cells
+    self.camera.y = std::cmp::min(target.y.saturating_sub(self.view.y >> 1), max.y) & !1;
+  }
+
+  pub fn is_visible(&self, p: &Point) -> bool {
+    (self.camera.x..self.camera.x.saturating_add(self.view.x)).contains(&p.x)
+      && (self.camera.y..self.camera.y.saturating_add(self.view.y)).contains(&p.y)
+  }
+
+  // Top left terminal cell of the block a logical cell is drawn in
+  pub fn screen(&self, p: &Point) -> Point {
+    let cell = *p - self.camera;
+    Point::new(cell.x * self.scale.x, ((cell.y >> 1) * self.scale.y) << 1).offset(&Point::new(self.position.x + self.inset, self.position.y + self.inset))
+  }
+
+  // Fills the whole block of a logical cell with the glyph, cells outside the camera aren't drawn at all. A half
+  // or quarter block only covers its own half of the rows, drawn whole except for the middle row of odd heights
+  // so taller blocks don't come out striped
+  pub fn draw(&self, p: &Point, c: char, f: &mut Frame) -> fmt::Result {
+    if !self.is_visible(p) {
+      return Ok(());
+    }
+    let origin = self.screen(p);
+    let half = match c {
+      '▀' => Some((true, '█')),
+      '▄' => Some((false, '█')),
+      '▘' => Some((true, '▌')),
+      '▝' => Some((true, '▐')),
+      '▖' => Some((false, '▌')),
+      '▗' => Some((false, '▐')),
+      _ => None,
+    };
+    // Half rows the glyph's half covers out of the two each terminal row holds
+    let height = self.scale.y as u16;
+    let covered = |top: bool, row: u16| {
+      let (from, to) = if top { (0, height) } else { (height, height << 1) };
+      std::cmp::min(to, (row + 1) << 1).saturating_sub(std::cmp::max(from, row << 1))
+    };
+    for row in 0..self.scale.y {
+      let glyph = match half {
+        Some((top, whole)) => match covered(top, row as u16) {
+          0 => continue,
+          1 => c,
+          _ => whole,
+        },
+        None => c,
+      };
+      let glyph = self.charset.glyph(glyph);
+      for col in 0..self.scale.x {
+        Point::new(origin.x + col, origin.y + row).render(glyph, f)?;
+      }
     }
+    Ok(())
   }
 }
 
 impl Arena {
-  pub fn render(&mut self, f: &mut String, termsize: &Point, food: &mut [Food]) -> fmt::Result {
-    const PADDING: Point = Point::new(16, 2);
-    if self.position.x + self.size.x + PADDING.x > termsize.x {
-      let diff = (self.position.x + self.size.x + PADDING.x) - termsize.x;
-      let sub = diff.saturating_sub(self.position.x.saturating_sub(2));
-      self.position.x = self.position.x.saturating_sub(diff);
+  // Keeps the arena on screen, shrinking it to fit unless it scrolls through a viewport instead
+  pub fn fit(&mut self, termsize: &Point, food: &mut [Food]) {
+    let frame = self.inset << 1;
+    let padding = Point::new(16 + frame, 2 + frame);
+    if self.viewport {
+      self.position.x = std::cmp::min(self.position.x, termsize.x.saturating_sub(padding.x + 8));
+      self.position.y = std::cmp::min(self.position.y, termsize.y.saturating_sub(padding.y + 4));
+    } else if self.position.x as u16 + self.size.x as u16 * self.scale.x as u16 + padding.x as u16 > termsize.x as u16 {
+      let diff = (self.position.x as u16 + self.size.x as u16 * self.scale.x as u16 + padding.x as u16) - termsize.x as u16;
+      let sub = diff.saturating_sub(self.position.x.saturating_sub(2) as u16).div_ceil(self.scale.x as u16);
+      self.position.x = self.position.x.saturating_sub(diff.min(u8::MAX as u16) as u8);
       if sub != 0 {
-        self.shrink_width(sub, food);
+        self.shrink_width(sub.min(u8::MAX as u16) as u8, food);
       }
     }
-    if self.position.y + self.size.y + PADDING.y > termsize.y {
-      let diff = (self.position.y + self.size.y + PADDING.y) - termsize.y;
-      let sub = diff.saturating_sub(self.position.y.saturating_sub(3));
-      self.position.y = self.position.y.saturating_sub(diff);
+    if !self.viewport && self.position.y as u16 + self.size.y as u16 * self.scale.y as u16 + padding.y as u16 > termsize.y as u16 {
+      let diff = (self.position.y as u16 + self.size.y as u16 * self.scale.y as u16 + padding.y as u16) - termsize.y as u16;
+      let sub = diff.saturating_sub(self.position.y.saturating_sub(3) as u16).div_ceil(self.scale.y as u16);
+      self.position.y = self.position.y.saturating_sub(diff.min(u8::MAX as u16) as u8);
       if sub != 0 {
-        self.shrink_height(sub, food);
+        self.shrink_height(sub.min(u8::MAX as u16) as u8, food);
       }
     }
 
@@ -44,17 +224,107 @@ impl Arena {
     if self.position.y < 3 {
       self.position.y = 3;
     }
+    self.view = self.view(termsize);
+  }
+
+  pub fn render(&self, f: &mut String) -> fmt::Result {
+    if self.viewport {
+      self.render_viewport(f)?;
+      return self.render_grid(f);
+    }
+
+    let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] = ['╔', '╗', '╚', '╝', '═', '║'].map(|c| self.charset.glyph(c));
+    let window = self.window();
+    let edge: String = std::iter::repeat_n(horizontal, window.x as usize).collect();
+    writeln!(f, "\x1b[{};{}H{top_left}{edge}{top_right}", self.position.y, self.position.x)?;
+    for _ in 0..window.y {
+      writeln!(f, "\x1b[{}C{vertical}\x1b[{}C{vertical}", self.position.x.saturating_sub(1), window.x)?;
+    }
+    writeln!(f, "\x1b[{}C{bottom_left}{edge}{bottom_right}", self.position.x.saturating_sub(1))?;
+    self.render_inset(f)?;
+    self.render_grid(f)
+  }
+
+  // Only the edges of the arena that are inside the viewport get a border
+  fn render_viewport(&self, f: &mut String) -> fmt::Result {
+    let window = self.window();
+    let left = self.camera.x == 0;
+    let right = self.camera.x as u16 + self.view.x as u16 >= self.size.x as u16;
+    let top = self.camera.y == 0;
+    let bottom = self.camera.y as u16 + self.view.y as u16 >= self.height() as u16;
+    let corner = |horizontal: bool, vertical: bool, c: char| match (horizontal, vertical) {
+      (true, true) => c,
+      (true, false) => '═',
+      (false, true) => '║',
+      (false, false) => ' ',
+    };
+    let edge = |f: &mut String, y: u8, first: char, last: char| {
+      let line: String = std::iter::once(first).chain(std::iter::repeat_n('═', window.x as usize)).chain(std::iter::once(last)).collect();
+      write!(f, "\x1b[{};{}H{}", y, self.position.x, self.charset.text(&line))
+    };
 
-    writeln!(f, "\x1b[{};{}H╔{:═<3$}╗", self.position.y, self.position.x, "", self.size.x as usize)?;
-    for _ in 0..self.size.y {
-      writeln!(f, "\x1b[{}C║\x1b[{}C║", self.position.x.saturating_sub(1), self.size.x)?;
+    if top {
+      edge(f, self.position.y, corner(top, left, '╔'), corner(top, right, '╗'))?;
+    }
+    for row in 1..=window.y {
+      if left {
+        write!(f, "\x1b[{};{}H{}", self.position.y + row, self.position.x, self.charset.glyph('║'))?;
+      }
+      if right {
+        write!(f, "\x1b[{};{}H{}", self.position.y + row, self.position.x + window.x + 1, self.charset.glyph('║'))?;
+      }
+    }
+    if bottom {
+      edge(f, self.position.y + window.y + 1, corner(bottom, left, '╚'), corner(bottom, right, '╝'))?;
+    }
+    Ok(())
+  }
+
+  // Shades the frame between the border and the playable area
+  fn render_inset(&self, f: &mut String) -> fmt::Result {
+    if self.inset == 0 {
+      return Ok(());
+    }
+    let window = self.window();
+    let shade = self.charset.glyph('░');
+    let full: String = std::iter::repeat_n(shade, window.x as usize).collect();
+    let side: String = std::iter::repeat_n(shade, self.inset as usize).collect();
+    for row in 1..=window.y {
+      if row <= self.inset || row > window.y - self.inset {
+        write!(f, "\x1b[{};{}H{full}", self.position.y + row, self.position.x + 1)?;
+      } else {
+        write!(f, "\x1b[{};{}H{side}", self.position.y + row, self.position.x + 1)?;
+        write!(f, "\x1b[{};{}H{side}", self.position.y + row, self.position.x + window.x + 1 - self.inset)?;
+      }
     }
-    writeln!(f, "\x1b[{}C╚{:═<2$}╝", self.position.x.saturating_sub(1), "", self.size.x as usize)?;
     Ok(())
   }
 
+  fn render_grid(&self, f: &mut String) -> fmt::Result {
+    const GRID_COLOR: u8 = 238;
+    let Some(spacing) = self.grid.filter(|&n| n > 0) else {
+      return Ok(());
+    };
+
+    let spacing = spacing as usize;
+    fg(f, GRID_COLOR)?;
+    let window = Point::new(self.view.x, self.view.y >> 1);
+    for row in 0..window.y {
+      if !((self.camera.y >> 1) as usize + row as usize).is_multiple_of(spacing) {
+        continue;
+      }
+      for col in 0..window.x {
+        if (self.camera.x as usize + col as usize).is_multiple_of(spacing) {
+          let (y, x) = (self.position.y + self.inset + row * self.scale.y + 1, self.position.x + self.inset + col * self.scale.x + 1);
+          write!(f, "\x1b[{y};{x}H{}", self.charset.glyph('·'))?;
+        }
+      }
+    }
+    reset(f)
+  }
+
   pub fn shrink_width(&mut self, n: u8, food: &mut [Food]) {
-    self.size.x = std::cmp::max(8, self.size.x.saturating_sub(n));
+    self.size.x = std::cmp::max(MIN_ARENA, self.size.x.saturating_sub(n));
     let size = self.size.x - 2;
     for food in food {
       if food.x > size {
@@ -63,9 +333,37 @@ impl Arena {
     }
   }
 
+  pub fn resize_by(&mut self, (x, y): (i32, i32), food: &mut [Food]) {
+    let (w, h) = (x.unsigned_abs().min(u8::MAX as u32) as u8, y.unsigned_abs().min(u8::MAX as u32) as u8);
+    if x < 0 {
+      self.shrink_width(w, food);
+    } else {
+      self.size.x = self.size.x.saturating_add(w);
+    }
+    if y < 0 {
+      self.shrink_height(h, food);
+    } else {
+      self.grow_height(h);
+    }
+  }
+
+  // Fits the other dimension to `ratio` of logical width over logical height, so a ratio of 1 looks square
+  // on screen since each terminal row holds two logical rows
+  pub fn aspect_size(size: &Point, ratio: f32, width_changed: bool) -> Point {
+    if width_changed {
+      Point::new(size.x, (size.x as f32 / ratio / 2.).round().clamp(MIN_ARENA as f32, MAX_HEIGHT as f32) as u8)
+    } else {
+      Point::new((size.y as f32 * 2. * ratio).round().clamp(MIN_ARENA as f32, u8::MAX as f32) as u8, size.y)
+    }
+  }
+
+  pub fn grow_height(&mut self, n: u8) {
+    self.size.y = std::cmp::min(MAX_HEIGHT, self.size.y.saturating_add(n));
+  }
+
   pub fn shrink_height(&mut self, n: u8, food: &mut [Food]) {
-    self.size.y = std::cmp::max(8, self.size.y.saturating_sub(n));
-    let size = (self.size.y - 1) * 2;
+    self.size.y = std::cmp::max(MIN_ARENA, self.size.y.saturating_sub(n));
+    let size = self.height() - 1;
     for food in food {
       if food.y > size {
         food.position.y -= food.y - size;
@@ -74,7 +372,87 @@ impl Arena {
   }
 }
 
-#[derive(Clone, Copy)]
+// Which snakes cover each cell of the arena and with how many segments, so a crash check looks at a single
+// cell instead of every body. Occupants of a cell stay in snake order so the first one hit is the same
+// snake a scan of every body would find.
+#[derive(Default)]
+pub struct Occupancy {
+  width: usize,
+  height: usize,
+  cells: Vec<Vec<(usize, usize)>>,
+  bodies: Vec<Vec<Point>>,
+}
+
+impl Occupancy {
+  // Starts over when the arena was resized or the snakes were replaced, otherwise the grid is kept up to
+  // date one snake at a time through sync
+  pub fn refresh(&mut self, snakes: &[Snake], size: Point) {
+    if (self.width, self.height) == (size.x as usize, size.y as usize) && self.bodies.len() == snakes.len() {
+      return;
+    }
+    (self.width, self.height) = (size.x as usize, size.y as usize);
+    self.cells.resize_with(self.width * self.height, Vec::new);
+    self.cells.iter_mut().for_each(Vec::clear);
+    self.bodies.clear();
+    for idx in 0..snakes.len() {
+      self.sync(snakes, idx);
+    }
+  }
+
+  // Forces the next refresh to rebuild the whole grid
+  pub fn invalidate(&mut self) {
+    self.bodies.clear();
+    self.width = 0;
+  }
+
+  pub fn sync(&mut self, snakes: &[Snake], idx: usize) {
+    if self.bodies.len() <= idx {
+      self.bodies.resize_with(idx + 1, Vec::new);
+    }
+    let mut body = std::mem::take(&mut self.bodies[idx]);
+    for p in &body {
+      let Some(cell) = self.index(p).map(|i| &mut self.cells[i]) else {
+        continue;
+      };
+      if let Ok(i) = cell.binary_search_by_key(&idx, |&(snake, _)| snake) {
+        cell[i].1 -= 1;
+        if cell[i].1 == 0 {
+          cell.remove(i);
+        }
+      }
+    }
+    body.clear();
+    body.extend(snakes[idx].body.iter().copied());
+    for p in &body {
+      let Some(cell) = self.index(p).map(|i| &mut self.cells[i]) else {
+        continue;
+      };
+      match cell.binary_search_by_key(&idx, |&(snake, _)| snake) {
+        Ok(i) => cell[i].1 += 1,
+        Err(i) => cell.insert(i, (idx, 1)),
+      }
+    }
+    self.bodies[idx] = body;
+  }
+
+  // Snakes with segments on `p` and how many of their segments are there
+  pub fn occupants(&self, p: &Point) -> &[(usize, usize)] {
+    self.index(p).map_or(&[], |i| &self.cells[i])
+  }
+
+  fn index(&self, p: &Point) -> Option<usize> {
+    ((p.x as usize) < self.width && (p.y as usize) < self.height).then(|| p.y as usize * self.width + p.x as usize)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WallPolicy {
+  Kill,
+  Penalty(u8),
+  Bounce,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Strategy {
   Player,
   Speed,
@@ -82,6 +460,10 @@ pub enum Strategy {
   Eat,
   Kill,
   Cannibal,
+  Adaptive,
+  Weighted { food: f32, kill: f32, safety: f32 },
+  // Hunts the player together with the other pack snakes, each cutting off a different way out
+  Pack,
 }
 
 impl Strategy {
@@ -93,8 +475,44 @@ impl Strategy {
       Strategy::Eat => 195,
       Strategy::Kill => 210,
       Strategy::Cannibal => 190,
+      Strategy::Adaptive => 141,
+      Strategy::Weighted { .. } => 117,
+      Strategy::Pack => 166,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Strategy::Player => "Player",
+      Strategy::Speed => "Speed",
+      Strategy::Score => "Score",
+      Strategy::Eat => "Eat",
+      Strategy::Kill => "Kill",
+      Strategy::Cannibal => "Cannibal",
+      Strategy::Adaptive => "Adaptive",
+      Strategy::Weighted { .. } => "Weighted",
+      Strategy::Pack => "Pack",
+    }
+  }
+
+  // Body glyph telling strategies apart without color, plain ASCII so it reads the same in either charset
+  pub fn glyph(&self) -> char {
+    match self {
+      Strategy::Player => '@',
+      Strategy::Speed => '>',
+      Strategy::Score => '$',
+      Strategy::Eat => 'o',
+      Strategy::Kill => 'x',
+      Strategy::Cannibal => '#',
+      Strategy::Adaptive => '%',
+      Strategy::Weighted { .. } => '=',
+      Strategy::Pack => '&',
     }
   }
+
+  pub fn weighted(food: f32, kill: f32, safety: f32) -> Self {
+    Strategy::Weighted { food, kill, safety }
+  }
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -103,6 +521,64 @@ pub enum Effect {
   Speed,
   Nourish,
   Cannibal,
+  Magnet,
+  Split,
+  Swap,
+  // Food carrying a user defined FoodEffect
+  Custom,
+}
+
+// What eating a food does to the snake and how the food looks, the built-in effects implement it too so
+// custom ones can be added without touching Effect
+pub trait FoodEffect {
+  fn apply(&self, snake: &mut Snake);
+  fn glyph(&self) -> char;
+  fn color(&self) -> u8;
+}
+
+impl FoodEffect for Effect {
+  fn apply(&self, snake: &mut Snake) {
+    let mut growth = 1;
+    match self {
+      Effect::None => (),
+      // Too short to turn cannibal, the food still feeds the snake like plain food
+      Effect::Cannibal if !snake.can_turn_cannibal() => (),
+      Effect::Speed | Effect::Cannibal | Effect::Magnet => snake.add_effect(*self),
+      Effect::Nourish => growth += 1,
+      // The game splits the snake once it's done eating since that adds a snake
+      Effect::Split => (),
+      // Needs every snake, eat does the swap
+      Effect::Swap => (),
+      Effect::Custom => (),
+    }
+    snake.grow(growth);
+  }
+
+  fn glyph(&self) -> char {
+    match self {
+      Effect::None => '󰉛',
+      Effect::Speed => '',
+      Effect::Nourish => '󱩡',
+      Effect::Cannibal => '',
+      Effect::Magnet => '',
+      Effect::Split => '✂',
+      Effect::Swap => '⇄',
+      Effect::Custom => '?',
+    }
+  }
+
+  fn color(&self) -> u8 {
+    match self {
+      Effect::None => 41,
+      Effect::Speed => 226,
+      Effect::Nourish => 213,
+      Effect::Cannibal => 167,
+      Effect::Magnet => 39,
+      Effect::Split => 171,
+      Effect::Swap => 99,
+      Effect::Custom => 255,
+    }
+  }
 }
 
 impl From<usize> for Effect {
@@ -117,13 +593,18 @@ impl From<usize> for Effect {
 }
 
 pub const EFFECT_SECONDS: u64 = 10;
+const PULL_TRAIL_MS: u128 = 150;
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Food {
   shape: char,
   pub position: Point,
   color: u8,
   effect: Effect,
+  custom: Option<Rc<dyn FoodEffect>>,
+  pub spawned_at: Instant,
+  pulled_from: Option<(Point, Instant)>,
+  rotten_at: Option<Instant>,
 }
 
 impl Deref for Food {
@@ -135,31 +616,24 @@ impl Deref for Food {
 
 impl Food {
   pub fn new(effect: Effect, position: Point) -> Self {
-    match effect {
-      Effect::None => Self {
-        shape: '󰉛',
-        position,
-        color: 41,
-        effect,
-      },
-      Effect::Speed => Self {
-        shape: '',
-        position,
-        color: 226,
-        effect,
-      },
-      Effect::Nourish => Self {
-        shape: '󱩡',
-        position,
-        color: 213,
-        effect,
-      },
-      Effect::Cannibal => Self {
-        shape: '',
-        position,
-        color: 167,
-        effect,
-      },
+    Self {
+      shape: effect.glyph(),
+      position,
+      color: effect.color(),
+      effect,
+      custom: None,
+      spawned_at: Instant::now(),
+      pulled_from: None,
+      rotten_at: None,
+    }
+  }
+
+  pub fn custom(effect: Rc<dyn FoodEffect>, position: Point) -> Self {
+    Self {
+      shape: effect.glyph(),
+      color: effect.color(),
+      custom: Some(effect),
+      ..Self::new(Effect::Custom, position)
     }
   }
 
@@ -167,25 +641,172 @@ impl Food {
     Self::new(effect, Point::random(rng, end))
   }
 
-  pub fn render(&self, f: &mut String, offset: &Point) -> fmt::Result {
-    fg(f, self.color)?;
-    self.position.offset(offset).render(self.shape, f)?;
+  pub fn random_free(effect: Effect, rng: &mut Rng, end: &Point, snakes: &[Snake]) -> Self {
+    let mut food = Self::random(effect, rng, end);
+    food.place_free(rng, &Rect::new(0, 0, end.x, end.y << 1), snakes, &[]);
+    food
+  }
+
+  // Random tries first, a crowded region then picks among the cells still free and a full one leaves
+  // the food where it is. Cells in `taken` count as occupied too
+  fn place_free(&mut self, rng: &mut Rng, region: &Rect, snakes: &[Snake], taken: &[Point]) {
+    let is_free = |p: &Point| !is_occupied(p, snakes) && !taken.contains(p);
+    for _ in 0..PLACE_TRIES {
+      if region.contains(&self.position) && is_free(&self.position) {
+        return;
+      }
+      self.position.randomize_in(rng, region);
+    }
+    let free: Vec<Point> = (region.x..region.x.saturating_add(region.width))
+      .flat_map(|x| (region.y..region.y.saturating_add(region.height)).map(move |y| Point::new(x, y)))
+      .filter(is_free)
+      .collect();
+    if !free.is_empty() {
+      self.position = free[rng.generate(free.len())];
+    }
+  }
+
+  // Moves the food to a free cell as if it had just spawned there
+  pub fn relocate(&mut self, rng: &mut Rng, end: &Point, snakes: &[Snake]) {
+    self.relocate_in(rng, &Rect::new(0, 0, end.x, end.y << 1), snakes, &[]);
+  }
+
+  // Same as relocate but the new cell is picked within `region` and kept off the `taken` cells
+  pub fn relocate_in(&mut self, rng: &mut Rng, region: &Rect, snakes: &[Snake], taken: &[Point]) {
+    let mut position = Point::new(region.x, region.y);
+    position.randomize_in(rng, region);
+    let custom = self.custom.take();
+    *self = match custom {
+      Some(custom) => Self::custom(custom, position),
+      None => Self::new(self.effect, position),
+    };
+    self.place_free(rng, region, snakes, taken);
+  }
+
+  pub fn effect(&self) -> Effect {
+    self.effect
+  }
+
+  pub fn pull(&mut self, to: Point) {
+    self.pulled_from = Some((self.position, Instant::now()));
+    self.position = to;
+  }
+
+  pub fn is_expired(&self, lifetime: Option<Duration>) -> bool {
+    lifetime.is_some_and(|lifetime| self.spawned_at.elapsed() >= lifetime)
+  }
+
+  // Rotten food can't be eaten and kills whatever touches it
+  pub fn rot(&mut self) {
+    self.rotten_at = Some(Instant::now());
+  }
+
+  pub fn is_rotten(&self) -> bool {
+    self.rotten_at.is_some()
+  }
+
+  // Rotten food lingers for as long as it stayed fresh, up to ROT_SECONDS
+  pub fn is_decayed(&self, lifetime: Duration) -> bool {
+    self.rotten_at.is_some_and(|at| at.elapsed() >= lifetime.min(Duration::from_secs(ROT_SECONDS)))
+  }
+
+  pub fn render(&self, f: &mut Frame, arena: &Arena, lifetime: Option<Duration>) -> fmt::Result {
+    self.draw(f, arena, lifetime, self.color)
+  }
+
+  // Greyed out for food that would have no effect on the snake being watched
+  pub fn render_inert(&self, f: &mut Frame, arena: &Arena, lifetime: Option<Duration>) -> fmt::Result {
+    self.draw(f, arena, lifetime, INERT_COLOR)
+  }
+
+  fn draw(&self, f: &mut Frame, arena: &Arena, lifetime: Option<Duration>, color: u8) -> fmt::Result {
+    if !arena.is_visible(&self.position) {
+      return Ok(());
+    }
+    if self.is_rotten() {
+      fg(f, ROTTEN_COLOR)?;
+      arena.draw(&self.position, '✖', f)?;
+      return reset(f);
+    }
+    // Blinks through the last quarter of its lifetime
+    const BLINK_MS: u128 = 200;
+    if let Some(lifetime) = lifetime {
+      let age = self.spawned_at.elapsed();
+      if age >= lifetime - lifetime / 4 && (age.as_millis() / BLINK_MS) % 2 == 1 {
+        return Ok(());
+      }
+    }
+    fg(f, color)?;
+    // A faint trail behind food that was just dragged by a magnet
+    if let Some((from, at)) = self.pulled_from {
+      if at.elapsed().as_millis() < PULL_TRAIL_MS && arena.is_visible(&from) {
+        arena.draw(&from, '·', f)?;
+      }
+    }
+    arena.draw(&self.position, self.shape, f)?;
     reset(f)
   }
 
   pub fn apply_effect(&self, snake: &mut Snake) {
-    let mut growth = 1;
-    match self.effect {
-      Effect::None => (),
-      Effect::Speed => snake.add_speed(3),
-      Effect::Nourish => growth += 1,
-      Effect::Cannibal => snake.cannibal = Instant::now(),
+    match &self.custom {
+      Some(custom) => custom.apply(snake),
+      None => self.effect.apply(snake),
     }
-    let head = *snake.head();
-    snake.body.extend((0..growth).map(|_| head));
   }
 }
 
+const PLACE_TRIES: usize = 64;
+const PARTICLE_GLYPHS: [char; 2] = ['✦', '·'];
+pub const PARTICLE_FRAMES: u8 = 3;
+const ROT_SECONDS: u64 = 3;
+const ROTTEN_COLOR: u8 = 94;
+const INERT_COLOR: u8 = 243;
+
+pub struct Particle {
+  pub position: Point,
+  shape: char,
+  color: u8,
+  age: u8,
+}
+
+impl From<&Food> for Particle {
+  fn from(food: &Food) -> Self {
+    Self {
+      position: food.position,
+      shape: food.shape,
+      color: food.color,
+      age: 0,
+    }
+  }
+}
+
+impl Particle {
+  pub fn is_expired(&self) -> bool {
+    self.age >= PARTICLE_FRAMES
+  }
+
+  pub fn age(&mut self) {
+    self.age += 1;
+  }
+
+  pub fn render(&self, f: &mut Frame, arena: &Arena) -> fmt::Result {
+    if !arena.is_visible(&self.position) {
+      return Ok(());
+    }
+    let shape = match self.age {
+      0 => self.shape,
+      age => PARTICLE_GLYPHS[std::cmp::min(age as usize - 1, PARTICLE_GLYPHS.len() - 1)],
+    };
+    fg(f, self.color)?;
+    arena.draw(&self.position, shape, f)?;
+    reset(f)
+  }
+}
+
+pub fn is_occupied(p: &Point, snakes: &[Snake]) -> bool {
+  snakes.iter().any(|snake| snake.body.contains(p))
+}
+
 pub fn locate_food(food: &[Food], head: &Point, effect: Effect) -> Point {
   food
     .iter()
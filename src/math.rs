@@ -1,12 +1,12 @@
 use std::{
-  fmt::{self, Display, Write},
+  fmt,
   ops::{Add, Deref, DerefMut},
   time::SystemTime,
 };
 
-use crate::esc::mv;
+use crate::esc::{mv, Frame};
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Point {
   pub x: u8,
   pub y: u8,
@@ -23,9 +23,11 @@ impl Point {
     p
   }
 
-  pub fn render<C: Display>(&self, c: C, f: &mut String) -> fmt::Result {
+  pub fn render(&self, c: char, f: &mut Frame) -> fmt::Result {
     mv(f, self)?;
-    write!(f, "{c}")
+    f.push(c);
+    f.advance(self, c);
+    Ok(())
   }
 
   pub fn offset(&self, p: &Point) -> Point {
@@ -39,6 +41,18 @@ impl Point {
     self.x.abs_diff(p.x) as u32 + self.y.abs_diff(p.y) as u32
   }
 
+  // One cell closer to `target` along whichever axis is further off
+  pub fn step_toward(&self, target: &Point) -> Point {
+    let (dx, dy) = (self.x.abs_diff(target.x), self.y.abs_diff(target.y));
+    match (dx >= dy, self.x < target.x, self.y < target.y) {
+      _ if dx == 0 && dy == 0 => *self,
+      (true, true, _) => Point::new(self.x + 1, self.y),
+      (true, false, _) => Point::new(self.x - 1, self.y),
+      (false, _, true) => Point::new(self.x, self.y + 1),
+      (false, _, false) => Point::new(self.x, self.y - 1),
+    }
+  }
+
   pub fn distance(&self, p: &Point) -> u32 {
     let dx = (p.x as i32 - self.x as i32) as f32;
     let dy = (p.y as i32 - self.y as i32) as f32;
@@ -52,23 +66,65 @@ impl Point {
     let direction_v = if self.y > target.y { Direction::Up } else { Direction::Down };
     let distance_v = target.distance(&self.add(direction_v.coords()));
 
+    let (threshold_h, threshold_v) = Self::wrap_thresholds(bounds);
     if distance_h < distance_v {
-      if distance_h > ((bounds.x + 1) as u32) >> 1 {
+      if distance_h > threshold_h {
         [direction_h.inverse(), direction_h, direction_v, direction_v.inverse()]
       } else {
         [direction_h, direction_v, direction_v.inverse(), direction_h.inverse()]
       }
-    } else if distance_v > bounds.y as u32 + 2 {
+    } else if distance_v > threshold_v {
       [direction_v.inverse(), direction_v, direction_h, direction_h.inverse()]
     } else {
       [direction_v, direction_h, direction_h.inverse(), direction_v.inverse()]
     }
   }
 
+  // Distances past these are shorter to reach by wrapping around the arena
+  fn wrap_thresholds(bounds: &Point) -> (u32, u32) {
+    ((bounds.x as u32 + 1) >> 1, bounds.y as u32 + 2)
+  }
+
   pub fn randomize(&mut self, rng: &mut Rng, end: &Point) {
     self.x = rng.generate(end.x as usize) as u8;
     self.y = rng.generate((end.y as usize) << 1) as u8;
   }
+
+  pub fn randomize_in(&mut self, rng: &mut Rng, rect: &Rect) {
+    self.x = rect.x + rng.generate(rect.width.max(1) as usize) as u8;
+    self.y = rect.y + rng.generate(rect.height.max(1) as usize) as u8;
+  }
+}
+
+// Area in arena cells, the height counts half rows like every other logical y
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+  pub x: u8,
+  pub y: u8,
+  pub width: u8,
+  pub height: u8,
+}
+
+impl Rect {
+  pub const fn new(x: u8, y: u8, width: u8, height: u8) -> Self {
+    Self { x, y, width, height }
+  }
+
+  pub fn contains(&self, p: &Point) -> bool {
+    (self.x as u16..self.x as u16 + self.width as u16).contains(&(p.x as u16))
+      && (self.y as u16..self.y as u16 + self.height as u16).contains(&(p.y as u16))
+  }
+
+  // Shrinks the rect to fit within `end`, keeping at least one cell
+  pub fn clamp(&self, end: &Point) -> Self {
+    let (x, y) = (self.x.min(end.x.saturating_sub(1)), self.y.min(end.y.saturating_sub(1)));
+    Self {
+      x,
+      y,
+      width: self.width.min(end.x - x).max(1),
+      height: self.height.min(end.y - y).max(1),
+    }
+  }
 }
 
 impl From<(u32, u32)> for Point {
@@ -110,25 +166,29 @@ impl std::ops::Add<(i32, i32)> for Point {
   type Output = Point;
   fn add(self, rhs: (i32, i32)) -> Self::Output {
     Self {
-      x: std::cmp::min(255, std::cmp::max(self.x as i32 + rhs.0, 0)) as u8,
-      y: std::cmp::min(255, std::cmp::max(self.y as i32 + rhs.1, 0)) as u8,
+      x: (self.x as i32 + rhs.0).clamp(0, 255) as u8,
+      y: (self.y as i32 + rhs.1).clamp(0, 255) as u8,
     }
   }
 }
 
-pub struct Rng(usize);
+pub struct Rng(u64);
 
 impl Rng {
   pub fn new() -> Self {
-    Self(SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_nanos() as usize)
+    Self::seeded(SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_nanos() as u64)
+  }
+
+  pub fn seeded(seed: u64) -> Self {
+    Self(seed)
   }
 
   pub fn generate(&mut self, max: usize) -> usize {
-    const LCG_MULT: usize = 1664525;
-    const LCG_INCR: usize = 1013904223;
-    self.0 ^= (&max as *const usize) as usize;
+    const LCG_MULT: u64 = 6364136223846793005;
+    const LCG_INCR: u64 = 1442695040888963407;
     self.0 = self.0.wrapping_mul(LCG_MULT).wrapping_add(LCG_INCR);
-    self.0 % max
+    // The low bits of an LCG cycle quickly so the result is taken from the high bits
+    (self.0 >> 32) as usize % max
   }
 }
 
@@ -150,11 +210,8 @@ impl DerefMut for ColoredPoint {
   }
 }
 
-pub fn cycle_back<T>(v: &[T], i: &mut usize) -> usize {
-  let r = *i;
-  *i = if r == 0 { v.len() - 1 } else { r - 1 };
-  r
-}
+// Key sets list the keys steering up, left, down and right in that order
+pub const WASD: [u8; 4] = *b"wasd";
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Direction {
@@ -174,6 +231,26 @@ impl Direction {
     }
   }
 
+  // Where a key in a key set steers, the default player keys are WASD
+  pub fn from_key(keys: &[u8; 4], b: u8) -> Option<Self> {
+    let dir = keys.iter().position(|&key| key == b)?;
+    Some([Direction::Up, Direction::Left, Direction::Down, Direction::Right][dir])
+  }
+
+  pub fn from_wasd(b: u8) -> Option<Self> {
+    Self::from_key(&WASD, b)
+  }
+
+  pub fn from_arrow(b: u8) -> Option<Self> {
+    match b {
+      b'A' => Some(Direction::Up),
+      b'C' => Some(Direction::Right),
+      b'B' => Some(Direction::Down),
+      b'D' => Some(Direction::Left),
+      _ => None,
+    }
+  }
+
   pub fn inverse(&self) -> Self {
     match self {
       Direction::Up => Direction::Down,
@@ -183,6 +260,17 @@ impl Direction {
     }
   }
 
+  // Direction of a neighboring cell, where neighbors further than one cell apart are joined across a wrapped edge
+  pub fn between(from: &Point, to: &Point) -> Option<Self> {
+    if from.y == to.y && from.x != to.x {
+      Some(if (from.x < to.x) == (from.x.abs_diff(to.x) == 1) { Direction::Right } else { Direction::Left })
+    } else if from.x == to.x && from.y != to.y {
+      Some(if (from.y < to.y) == (from.y.abs_diff(to.y) == 1) { Direction::Down } else { Direction::Up })
+    } else {
+      None
+    }
+  }
+
   pub fn coords(&self) -> (i8, i8) {
     match self {
       Direction::Up => (0, -1),
@@ -192,3 +280,64 @@ impl Direction {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn wrap_thresholds_do_not_overflow() {
+    assert_eq!(Point::wrap_thresholds(&Point::new(255, 255)), (128, 257));
+  }
+
+  #[test]
+  fn nearest_directions_wrap_near_the_edge() {
+    let bounds = Point::new(255, 100);
+    let from = Point::new(250, 50);
+
+    assert_eq!(from.nearest_directions(&Point::new(5, 50), &bounds)[0], Direction::Right);
+    assert_eq!(from.nearest_directions(&Point::new(240, 50), &bounds)[0], Direction::Left);
+  }
+
+  #[test]
+  fn step_toward_closes_the_wider_gap() {
+    let p = Point::new(10, 10);
+    assert_eq!(p.step_toward(&Point::new(4, 12)), Point::new(9, 10));
+    assert_eq!(p.step_toward(&Point::new(11, 20)), Point::new(10, 11));
+    assert_eq!(p.step_toward(&p), p);
+  }
+
+  #[test]
+  fn randomize_in_stays_inside_the_clamped_rect() {
+    let mut rng = Rng::seeded(3);
+    let rect = Rect::new(30, 40, 20, 20).clamp(&Point::new(40, 48));
+    assert_eq!(rect, Rect::new(30, 40, 10, 8));
+    let mut p = Point::new(0, 0);
+    for _ in 0..100 {
+      p.randomize_in(&mut rng, &rect);
+      assert!(rect.contains(&p));
+    }
+  }
+
+  #[test]
+  fn direction_between_neighbors_wraps() {
+    let p = Point::new(0, 5);
+    assert_eq!(Direction::between(&p, &Point::new(1, 5)), Some(Direction::Right));
+    assert_eq!(Direction::between(&p, &Point::new(39, 5)), Some(Direction::Left));
+    assert_eq!(Direction::between(&p, &Point::new(0, 4)), Some(Direction::Up));
+    assert_eq!(Direction::between(&p, &p), None);
+  }
+
+  #[test]
+  fn keys_map_to_directions() {
+    use Direction::*;
+    for (wasd, arrow, dir) in [(b'w', b'A', Up), (b'd', b'C', Right), (b's', b'B', Down), (b'a', b'D', Left)] {
+      assert_eq!(Direction::from_wasd(wasd), Some(dir));
+      assert_eq!(Direction::from_arrow(arrow), Some(dir));
+    }
+    for b in [b'W', b'x', b'E', b'p', b'\x1b', 0] {
+      assert_eq!(Direction::from_wasd(b), None);
+      assert_eq!(Direction::from_arrow(b), None);
+    }
+  }
+}
@@ -0,0 +1,179 @@
+use std::{
+  io::{self, Write},
+  panic::{self, PanicHookInfo},
+  sync::{Arc, Mutex},
+  time::{Duration, Instant},
+};
+
+type PanicHook = Box<dyn Fn(&PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlushPolicy {
+  PerFrame,
+  Interval(Duration),
+  BufferFull(usize),
+}
+
+pub struct FrameWriter<W: Write> {
+  out: W,
+  policy: FlushPolicy,
+  pending: usize,
+  flushed_at: Instant,
+}
+
+impl<W: Write> FrameWriter<W> {
+  pub fn new(out: W, policy: FlushPolicy) -> Self {
+    Self {
+      out,
+      policy,
+      pending: 0,
+      flushed_at: Instant::now(),
+    }
+  }
+
+  pub fn write_frame(&mut self, frame: &str) -> io::Result<()> {
+    self.out.write_all(frame.as_bytes())?;
+    self.pending += frame.len();
+
+    let flush = match self.policy {
+      FlushPolicy::PerFrame => true,
+      FlushPolicy::Interval(interval) => self.flushed_at.elapsed() >= interval,
+      FlushPolicy::BufferFull(size) => self.pending >= size,
+    };
+    if flush {
+      self.flush()?;
+    }
+    Ok(())
+  }
+
+  pub fn flush(&mut self) -> io::Result<()> {
+    self.pending = 0;
+    self.flushed_at = Instant::now();
+    self.out.flush()
+  }
+}
+
+impl<W: Write> Drop for FrameWriter<W> {
+  fn drop(&mut self) {
+    // Make sure the last frame and terminal restore sequences are never left in the buffer, unless
+    // unwinding where a half drawn frame would undo what the panic hook restored
+    if !std::thread::panicking() {
+      let _ = self.flush();
+    }
+  }
+}
+
+// Writes the terminal restore sequences before the previous panic hook reports a panic, the previous
+// hook is put back once this is dropped
+pub struct RestoreOnPanic {
+  previous: Arc<PanicHook>,
+}
+
+impl RestoreOnPanic {
+  pub fn install<W: Write + Send + 'static>(out: W, restore: &str) -> Self {
+    let previous: Arc<PanicHook> = Arc::new(panic::take_hook());
+    let chained = Arc::clone(&previous);
+    let out = Mutex::new(out);
+    let restore = restore.to_string();
+    panic::set_hook(Box::new(move |info| {
+      if let Ok(mut out) = out.lock() {
+        let _ = out.write_all(restore.as_bytes());
+        let _ = out.flush();
+      }
+      chained(info);
+    }));
+    Self { previous }
+  }
+}
+
+impl Drop for RestoreOnPanic {
+  fn drop(&mut self) {
+    // The hook can't be swapped while panicking, it stays installed until the panic is handled
+    if std::thread::panicking() {
+      return;
+    }
+    let previous = Arc::clone(&self.previous);
+    drop(panic::take_hook());
+    panic::set_hook(Box::new(move |info| previous(info)));
+  }
+}
+
+// Writes the restore sequence once dropped so the terminal is put back however the game loop ends,
+// including an early return on an error
+pub struct RestoreOnExit<W: Write> {
+  out: W,
+  restore: String,
+}
+
+impl<W: Write> RestoreOnExit<W> {
+  pub fn new(out: W, restore: &str) -> Self {
+    Self { out, restore: restore.to_string() }
+  }
+}
+
+impl<W: Write> Drop for RestoreOnExit<W> {
+  fn drop(&mut self) {
+    // A panic already went through the hook of RestoreOnPanic
+    if std::thread::panicking() {
+      return;
+    }
+    let _ = self.out.write_all(self.restore.as_bytes());
+    let _ = self.out.flush();
+  }
+}
+
+// Turns the time elapsed between loop iterations into whole frames. Time left over from a late frame
+// carries into the next one, but at most max_frameskip frames are owed at once so a frame that runs
+// long can't leave the loop forever catching up. Gaps longer than max_catchup, like the process being
+// suspended, only count up to max_catchup
+pub struct FrameClock {
+  frame: Duration,
+  max_frameskip: u32,
+  max_catchup: Duration,
+  accumulator: Duration,
+}
+
+impl FrameClock {
+  pub fn new(frame: Duration, max_frameskip: u32) -> Self {
+    Self {
+      frame,
+      max_frameskip: max_frameskip.max(1),
+      max_catchup: Duration::MAX,
+      accumulator: Duration::ZERO,
+    }
+  }
+
+  pub fn set_frame(&mut self, frame: Duration) {
+    self.frame = frame;
+  }
+
+  pub fn set_max_frameskip(&mut self, max_frameskip: u32) {
+    self.max_frameskip = max_frameskip.max(1);
+  }
+
+  pub fn set_max_catchup(&mut self, max_catchup: Duration) {
+    self.max_catchup = max_catchup;
+  }
+
+  pub fn accumulator(&self) -> Duration {
+    self.accumulator
+  }
+
+  // Returns how many frames became due, only the latest of which is worth drawing
+  pub fn advance(&mut self, elapsed: Duration) -> u32 {
+    if self.frame.is_zero() {
+      return 1;
+    }
+    self.accumulator = self.accumulator.saturating_add(elapsed.min(self.max_catchup));
+    let mut due = 0;
+    while self.accumulator >= self.frame {
+      self.accumulator -= self.frame;
+      due += 1;
+      if due == self.max_frameskip {
+        self.accumulator = Duration::from_nanos((self.accumulator.as_nanos() % self.frame.as_nanos()) as u64);
+        break;
+      }
+    }
+    due
+  }
+}
@@ -0,0 +1,80 @@
+use crate::{charset::CharSet, math::Point};
+use std::{
+  fmt::Write,
+  fs, io,
+  path::{Path, PathBuf},
+};
+
+// Choices kept across runs, anything left out of the file keeps the game's default. Keys are written as
+// byte values so bindings like space survive editors trimming lines.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Preferences {
+  path: Option<PathBuf>,
+  pub player_keys: Vec<[u8; 4]>,
+  pub dash_key: Option<u8>,
+  pub fps: Option<usize>,
+  pub arena_size: Option<Point>,
+  pub charset: Option<CharSet>,
+}
+
+impl Preferences {
+  // A missing file loads as all defaults, lines that don't parse are skipped
+  pub fn load(path: &Path) -> Self {
+    let mut preferences = Self {
+      path: Some(path.to_path_buf()),
+      ..Default::default()
+    };
+    let contents = fs::read_to_string(path).unwrap_or_default();
+    for ln in contents.lines() {
+      let (name, value) = ln.split_once(' ').unwrap_or((ln, ""));
+      let numbers: Option<Vec<usize>> = value.split_whitespace().map(|n| n.parse().ok()).collect();
+      match (name, numbers.as_deref(), value.trim()) {
+        ("keys", Some(&[up, left, down, right]), _) => {
+          if let [Ok(up), Ok(left), Ok(down), Ok(right)] = [up, left, down, right].map(u8::try_from) {
+            preferences.player_keys.push([up, left, down, right]);
+          }
+        }
+        ("dash", Some(&[key]), _) => preferences.dash_key = u8::try_from(key).ok(),
+        ("fps", Some(&[fps]), _) if fps > 0 => preferences.fps = Some(fps),
+        ("arena", Some(&[width, height]), _) => {
+          if let (Ok(width), Ok(height)) = (u8::try_from(width), u8::try_from(height)) {
+            preferences.arena_size = Some(Point::new(width, height));
+          }
+        }
+        ("charset", _, "unicode") => preferences.charset = Some(CharSet::Unicode),
+        ("charset", _, "ascii") => preferences.charset = Some(CharSet::Ascii),
+        _ => (),
+      }
+    }
+    preferences
+  }
+
+  pub fn path(&self) -> Option<&Path> {
+    self.path.as_deref()
+  }
+
+  pub fn save(&self) -> io::Result<()> {
+    let Some(path) = &self.path else {
+      return Ok(());
+    };
+    let mut contents = String::new();
+    for [up, left, down, right] in &self.player_keys {
+      let _ = writeln!(contents, "keys {up} {left} {down} {right}");
+    }
+    if let Some(key) = self.dash_key {
+      let _ = writeln!(contents, "dash {key}");
+    }
+    if let Some(fps) = self.fps {
+      let _ = writeln!(contents, "fps {fps}");
+    }
+    if let Some(size) = self.arena_size {
+      let _ = writeln!(contents, "arena {} {}", size.x, size.y);
+    }
+    match self.charset {
+      Some(CharSet::Unicode) => contents.push_str("charset unicode\n"),
+      Some(CharSet::Ascii) => contents.push_str("charset ascii\n"),
+      None => (),
+    }
+    fs::write(path, contents)
+  }
+}
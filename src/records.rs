@@ -0,0 +1,62 @@
+use std::{
+  collections::BTreeMap,
+  fs, io,
+  path::{Path, PathBuf},
+};
+
+#[derive(Default)]
+pub struct Records {
+  path: Option<PathBuf>,
+  best: BTreeMap<String, usize>,
+}
+
+impl Records {
+  // A missing or unreadable file just starts with no records
+  pub fn load(path: &Path) -> Self {
+    let best = fs::read_to_string(path)
+      .map(|contents| {
+        contents
+          .lines()
+          .filter_map(|ln| {
+            let (name, score) = ln.rsplit_once(' ')?;
+            Some((name.to_string(), score.parse().ok()?))
+          })
+          .collect()
+      })
+      .unwrap_or_default();
+
+    Self {
+      path: Some(path.to_path_buf()),
+      best,
+    }
+  }
+
+  pub fn save(&self) -> io::Result<()> {
+    let Some(path) = &self.path else {
+      return Ok(());
+    };
+    let contents: String = self.best.iter().map(|(name, score)| format!("{name} {score}\n")).collect();
+    fs::write(path, contents)
+  }
+
+  pub fn update(&mut self, name: &str, score: usize) {
+    match self.best.get_mut(name) {
+      Some(best) => *best = std::cmp::max(*best, score),
+      None => {
+        self.best.insert(name.to_string(), score);
+      }
+    }
+  }
+
+  pub fn get(&self, name: &str) -> Option<usize> {
+    self.best.get(name).copied()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+    self.best.iter().map(|(name, score)| (name.as_str(), *score))
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.best.is_empty()
+  }
+}
@@ -0,0 +1,52 @@
+use crate::snake::Snake;
+use std::{fmt::Write, fs, io, path::Path};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+  Json,
+  Csv,
+}
+
+const CSV_HEADER: &str = "name,strategy,color,length,kills,deaths,survived";
+
+// Final standings, one row per snake in spawn order with the survival time in seconds
+pub fn export(path: &Path, format: Format, snakes: &[Snake]) -> io::Result<()> {
+  let mut contents = String::new();
+  let rows = snakes.iter().map(|snake| (snake, snake.stats()));
+  match format {
+    Format::Csv => {
+      contents.push_str(CSV_HEADER);
+      contents.push('\n');
+      for (snake, stats) in rows {
+        let (name, strategy) = (snake.name, snake.strategy().name());
+        let _ = writeln!(
+          contents,
+          "{name},{strategy},{},{},{},{},{:.3}",
+          snake.color,
+          snake.len(),
+          stats.kills,
+          stats.deaths,
+          stats.survived.as_secs_f32()
+        );
+      }
+    }
+    Format::Json => {
+      let rows: Vec<_> = rows
+        .map(|(snake, stats)| {
+          format!(
+            r#"  {{"name": {:?}, "strategy": {:?}, "color": {}, "length": {}, "kills": {}, "deaths": {}, "survived": {:.3}}}"#,
+            snake.name,
+            snake.strategy().name(),
+            snake.color,
+            snake.len(),
+            stats.kills,
+            stats.deaths,
+            stats.survived.as_secs_f32()
+          )
+        })
+        .collect();
+      contents = format!("[\n{}\n]\n", rows.join(",\n"));
+    }
+  }
+  fs::write(path, contents)
+}
@@ -1,92 +1,541 @@
-use crate::consts::SNAKE_NAMES;
-use crate::esc::{bg, fg, reset};
-use crate::map::{locate_food, Arena, Effect, Food, Strategy, EFFECT_SECONDS};
-use crate::math::{cycle_back, ColoredPoint, Direction, Point, Rng};
+use crate::consts::{SNAKE_COLORS, SNAKE_NAMES};
+use crate::esc::{bg, fg, reset, shade, Frame};
+use crate::map::{is_occupied, locate_food, Arena, Effect, Food, Occupancy, Strategy, WallPolicy, EFFECT_SECONDS};
+use crate::math::{ColoredPoint, Direction, Point, Rect, Rng};
+use std::collections::VecDeque;
 use std::time::Duration;
-use std::{fmt, time::Instant};
+use std::{
+  fmt::{self, Write},
+  time::Instant,
+};
+
+const BLINK_FRAMES: usize = 6;
+const PROTECTED_COLOR: u8 = 240;
+const MEMORY: usize = 32;
+const MAX_TURNS: usize = 2;
+const MIN_SPLIT_LEN: usize = 4;
+const SPEED_DOSE: u8 = 3;
+const RESPAWN_SPEED: u8 = 80;
+// How much darker the tail gets than the head with a body gradient
+const GRADIENT_DIM: f32 = 0.6;
+const MAX_STACK: u32 = 3;
+// Pack intercepts sit this many cells ahead of the prey with the flanks this far to either side
+const PACK_LEAD: i32 = 4;
+const PACK_SPREAD: i32 = 3;
+
+// Lifetime totals for a snake across respawns, kills made in earlier lives still count here
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SnakeStats {
+  pub kills: usize,
+  pub deaths: usize,
+  pub survived: Duration,
+}
+
+pub struct CannibalStyle {
+  pub head: u8,
+  pub pulse: u8,
+  pub pulse_frames: usize,
+  pub body: Option<u8>,
+}
+
+impl CannibalStyle {
+  pub fn head_color(&self, frame: usize) -> u8 {
+    if (frame / self.pulse_frames.max(1)).is_multiple_of(2) {
+      self.head
+    } else {
+      self.pulse
+    }
+  }
+}
+
+impl Default for CannibalStyle {
+  fn default() -> Self {
+    Self {
+      head: 196,
+      pulse: 226,
+      pulse_frames: 8,
+      body: None,
+    }
+  }
+}
+
+// AI tuning, kill targets must be slower by more than kill_margin and cannibals only chase tails of
+// snakes slower by more than flee_margin that are longer than min_target_len. Adaptive snakes run from
+// faster cannibals within aggro_radius
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrategyParams {
+  pub kill_margin: u8,
+  pub flee_margin: u8,
+  pub min_target_len: usize,
+  pub aggro_radius: u32,
+}
+
+impl Default for StrategyParams {
+  fn default() -> Self {
+    Self {
+      kill_margin: 10,
+      flee_margin: 4,
+      min_target_len: 3,
+      aggro_radius: 12,
+    }
+  }
+}
+
+// How a dead snake sheds its body before respawning, a few segments per move or all of it within a
+// fixed time no matter how long it was
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeathShrink {
+  PerMove(u8),
+  Within(Duration),
+}
+
+// How eating an effect combines with the same effect still running. Extend adds to what's left up to
+// MAX_STACK doses, Refresh starts over from a single dose and Replace ends every other effect but leaves
+// an effect that's already running alone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StackRule {
+  Refresh,
+  Extend,
+  Replace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stacking {
+  pub speed: StackRule,
+  pub cannibal: StackRule,
+  pub magnet: StackRule,
+}
+
+impl Default for Stacking {
+  fn default() -> Self {
+    Self {
+      speed: StackRule::Extend,
+      cannibal: StackRule::Refresh,
+      magnet: StackRule::Refresh,
+    }
+  }
+}
+
+impl Stacking {
+  // Effects without a lasting state only ever refresh
+  pub fn rule(&self, effect: Effect) -> StackRule {
+    match effect {
+      Effect::Speed => self.speed,
+      Effect::Cannibal => self.cannibal,
+      Effect::Magnet => self.magnet,
+      _ => StackRule::Refresh,
+    }
+  }
+
+  pub fn set(&mut self, effect: Effect, rule: StackRule) {
+    match effect {
+      Effect::Speed => self.speed = rule,
+      Effect::Cannibal => self.cannibal = rule,
+      Effect::Magnet => self.magnet = rule,
+      _ => (),
+    }
+  }
+}
+
+// Points per segment, per second survived since the last respawn and per kill
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreWeights {
+  pub length: usize,
+  pub survival: usize,
+  pub kills: usize,
+}
+
+impl Default for ScoreWeights {
+  fn default() -> Self {
+    Self {
+      length: 1,
+      survival: 0,
+      kills: 0,
+    }
+  }
+}
+
+// Who gets credit for a death, crashes into walls or your own body have no killer and can be left out of
+// the death count, kills among teammates are only rewarded when asked for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KillPolicy {
+  pub self_deaths: bool,
+  pub team_kills: bool,
+}
+
+impl Default for KillPolicy {
+  fn default() -> Self {
+    Self {
+      self_deaths: true,
+      team_kills: false,
+    }
+  }
+}
+
+// Where each pack snake last decided to cut the prey off, shared across the AI step so no two pack snakes
+// head for the same cell
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackPlan {
+  claims: Vec<Option<Point>>,
+}
+
+impl PackPlan {
+  pub fn claim(&mut self, idx: usize, p: Point) {
+    if self.claims.len() <= idx {
+      self.claims.resize(idx + 1, None);
+    }
+    self.claims[idx] = Some(p);
+  }
+
+  pub fn claimed(&self, idx: usize) -> Option<Point> {
+    self.claims.get(idx).copied().flatten()
+  }
+
+  pub fn clear(&mut self) {
+    self.claims.clear();
+  }
+}
+
+// What happens when a snake runs into its own body, truncating drops everything from the bitten segment on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelfCollision {
+  Lethal,
+  Ignore,
+  Truncate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OtherCollision {
+  Lethal,
+  Ignore,
+}
+
+// Who gets food when several heads are on it at once, the lowest index, nobody with the food moving away,
+// or everyone with half the growth each rounded up
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FoodContest {
+  FirstCome,
+  Contested,
+  Split,
+}
+
+// Dash charges held at most, time to earn one back and cells covered by a single dash
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dash {
+  pub charges: u8,
+  pub recharge: Duration,
+  pub distance: u8,
+}
+
+impl Default for Dash {
+  fn default() -> Self {
+    Self {
+      charges: 0,
+      recharge: Duration::from_secs(5),
+      distance: 4,
+    }
+  }
+}
 
 pub struct Snake {
   pub name: &'static str,
   pub color: u8,
-  pub body: Vec<Point>,
+  pub body: VecDeque<Point>,
+  // When the cannibal and magnet effects started, stacked doses can make them last past EFFECT_SECONDS
   pub cannibal: Instant,
-  head: usize,
+  pub magnet: Instant,
+  pub max_len: Option<usize>,
+  pub spawn_protection: Duration,
+  pub strategy_params: StrategyParams,
+  pub smooth: bool,
+  pub gradient: bool,
+  // Draws the body on both edges while it wraps, otherwise only the part that already came through shows
+  pub straddle: bool,
+  // Drawn over the whole cell instead of the half blocks
+  pub glyph: Option<char>,
+  pub cannibal_min_len: usize,
+  pub max_boost: u8,
+  pub team: u8,
+  pub god_mode: bool,
+  pub tron: bool,
+  pub self_collision: SelfCollision,
+  pub other_collision: OtherCollision,
+  pub score_weights: ScoreWeights,
+  pub dash: Dash,
+  pub reaction: u8,
+  pub rate_limited: bool,
+  pub turn_cooldown: u8,
+  pub stacking: Stacking,
+  pub kill_policy: KillPolicy,
+  pub death_shrink: DeathShrink,
+  pub spawn_region: Option<Rect>,
+  think_timer: u8,
+  dash_charges: u8,
+  dash_recharged_at: Instant,
+  dashing: bool,
+  spawn_protected_until: Instant,
+  bonus: usize,
+  kills: usize,
+  ramped: u8,
+  totals: SnakeStats,
+  alive_since: Instant,
+  bite: Option<Instant>,
   dir: Direction,
   speed: u8,
+  boost: u8,
+  recent: VecDeque<Point>,
+  turns: VecDeque<Direction>,
+  took_control: bool,
+  moves: usize,
+  last_turn_tick: Option<usize>,
+  ticks: u32,
+  shrink_from: (Instant, usize),
+  cannibal_until: Instant,
+  magnet_until: Instant,
   delta: Instant,
   alive: bool,
   strat: Strategy,
 }
 
 impl Snake {
-  pub fn random(len: usize, strat: Strategy, rng: &mut Rng, end: &Point) -> Self {
+  // The whole body starts stacked on the head so the first move can't run into it whichever way it faces,
+  // a random direction is picked unless one is given
+  pub fn random(len: usize, strat: Strategy, dir: Option<Direction>, rng: &mut Rng, end: &Point) -> Self {
     Self {
       name: SNAKE_NAMES[rng.generate(SNAKE_NAMES.len())],
       color: strat.color(),
-      body: vec![Point::random(rng, end); len],
-      head: len - 1,
-      dir: Direction::random(rng),
+      body: VecDeque::from(vec![Point::random(rng, end); len]),
+      dir: dir.unwrap_or_else(|| Direction::random(rng)),
       speed: 55,
+      boost: 0,
+      recent: VecDeque::with_capacity(MEMORY + 1),
+      turns: VecDeque::with_capacity(MAX_TURNS),
+      took_control: false,
+      moves: 0,
+      last_turn_tick: None,
+      ticks: 0,
+      shrink_from: (Instant::now(), len),
+      cannibal_until: Instant::now(),
+      magnet_until: Instant::now(),
       delta: Instant::now(),
       alive: true,
       strat,
       cannibal: Instant::now() - Duration::from_secs(EFFECT_SECONDS),
+      magnet: Instant::now() - Duration::from_secs(EFFECT_SECONDS),
+      bite: None,
+      max_len: None,
+      spawn_protection: Duration::ZERO,
+      strategy_params: StrategyParams::default(),
+      smooth: false,
+      gradient: false,
+      straddle: true,
+      glyph: None,
+      cannibal_min_len: 0,
+      max_boost: u8::MAX,
+      team: 0,
+      god_mode: false,
+      tron: false,
+      self_collision: SelfCollision::Lethal,
+      other_collision: OtherCollision::Lethal,
+      score_weights: ScoreWeights::default(),
+      dash: Dash::default(),
+      reaction: 1,
+      rate_limited: false,
+      turn_cooldown: 0,
+      stacking: Stacking::default(),
+      kill_policy: KillPolicy::default(),
+      death_shrink: DeathShrink::PerMove(1),
+      spawn_region: None,
+      think_timer: 0,
+      dash_charges: 0,
+      dash_recharged_at: Instant::now(),
+      dashing: false,
+      spawn_protected_until: Instant::now(),
+      bonus: 0,
+      kills: 0,
+      ramped: 0,
+      totals: SnakeStats::default(),
+      alive_since: Instant::now(),
     }
   }
 
   pub fn head(&self) -> &Point {
-    &self.body[self.head]
+    self.body.front().unwrap()
   }
 
   pub fn head_mut(&mut self) -> &mut Point {
-    &mut self.body[self.head]
+    self.body.front_mut().unwrap()
   }
 
-  pub fn tail_idx(&self) -> usize {
-    if self.head == 0 {
-      self.body.len() - 1
-    } else {
-      self.head - 1
-    }
+  pub fn tail(&self) -> &Point {
+    self.body.back().unwrap()
   }
 
-  pub fn tail(&self) -> &Point {
-    &self.body[self.tail_idx()]
+  pub fn move_interval(&self) -> Duration {
+    Duration::from_millis(self.interval() as u64)
   }
 
-  pub fn speed(&self) -> u8 {
-    u8::MAX - self.speed
+  pub fn cells_per_second(&self) -> f32 {
+    1000. / self.interval().max(1) as f32
   }
 
   pub fn can_move(&mut self) -> bool {
-    if self.delta.elapsed().as_millis() >= self.speed as u128 {
+    if self.delta.elapsed().as_millis() >= self.interval() as u128 {
       self.delta = Instant::now();
       return true;
     }
     false
   }
 
+  // Counts simulation ticks instead of wall time, moving once every interval rounded to whole ticks
+  pub fn can_move_ticks(&mut self, tick: Duration) -> bool {
+    self.ticks += 1;
+    let every = (self.interval() as f32 * 1000. / tick.as_micros().max(1) as f32).round().max(1.) as u32;
+    if self.ticks >= every {
+      self.ticks = 0;
+      return true;
+    }
+    false
+  }
+
+  // AI snakes only rethink their target every `reaction` moves and keep their last decision in between
+  pub fn think(&mut self) -> bool {
+    if self.think_timer > 0 {
+      self.think_timer -= 1;
+      return false;
+    }
+    self.think_timer = self.reaction.saturating_sub(1);
+    true
+  }
+
   pub fn add_speed(&mut self, speed: u8) {
     self.speed = self.speed.saturating_sub(speed);
   }
 
-  pub fn render(&self, f: &mut String, arena: &Arena, top: &mut Vec<ColoredPoint>, bottom: &mut Vec<ColoredPoint>) -> fmt::Result {
+  // Speed from the difficulty ramp, unlike food it's kept through deaths
+  pub fn ramp_up(&mut self, speed: u8) {
+    self.add_speed(speed);
+    self.ramped = self.ramped.saturating_add(speed);
+  }
+
+  // Boost from food stacks on top of the base speed up to `max_boost`
+  pub fn add_boost(&mut self, boost: u8) {
+    self.boost = self.boost.saturating_add(boost).min(self.max_boost);
+  }
+
+  // Starts or stacks a lasting effect following its stacking rule
+  pub fn add_effect(&mut self, effect: Effect) {
+    let rule = self.stacking.rule(effect);
+    if rule == StackRule::Replace {
+      if self.effect_left(effect).is_some() {
+        return;
+      }
+      self.boost = 0;
+      self.end_effects();
+    }
+    match effect {
+      Effect::Speed if rule == StackRule::Extend => self.add_boost(SPEED_DOSE),
+      Effect::Speed => self.boost = SPEED_DOSE.min(self.max_boost),
+      Effect::Cannibal => Self::stack(&mut self.cannibal, &mut self.cannibal_until, rule),
+      Effect::Magnet => Self::stack(&mut self.magnet, &mut self.magnet_until, rule),
+      _ => (),
+    }
+  }
+
+  // Extending keeps the start of an effect that's still running, anything else starts it over
+  fn stack(started: &mut Instant, until: &mut Instant, rule: StackRule) {
+    let (now, dose, end) = (Instant::now(), Duration::from_secs(EFFECT_SECONDS), Self::effect_end(*started, *until));
+    if rule != StackRule::Extend || end <= now {
+      *started = now;
+    }
+    *until = match rule {
+      StackRule::Extend => (end.max(now) + dose).min(now + dose * MAX_STACK),
+      _ => now + dose,
+    };
+  }
+
+  // An effect lasts a dose from when it started or until whatever was stacked on top of it runs out
+  fn effect_end(started: Instant, until: Instant) -> Instant {
+    until.max(started + Duration::from_secs(EFFECT_SECONDS))
+  }
+
+  fn end_effects(&mut self) {
+    let expired = Instant::now() - Duration::from_secs(EFFECT_SECONDS);
+    (self.cannibal, self.cannibal_until, self.magnet, self.magnet_until) = (expired, expired, expired, expired);
+  }
+
+  // Time left on a running cannibal or magnet effect, a speed boost lasts until death so it has none
+  pub fn effect_left(&self, effect: Effect) -> Option<Duration> {
+    let end = match effect {
+      Effect::Speed => return (self.boost > 0).then_some(Duration::MAX),
+      Effect::Cannibal => Self::effect_end(self.cannibal, self.cannibal_until),
+      Effect::Magnet => Self::effect_end(self.magnet, self.magnet_until),
+      _ => return None,
+    };
+    Some(end.saturating_duration_since(Instant::now())).filter(|left| !left.is_zero())
+  }
+
+  pub fn boost(&self) -> u8 {
+    self.boost
+  }
+
+  pub fn boost_meter(&self, f: &mut String, width: usize) -> fmt::Result {
+    let filled = (self.boost as usize * width).checked_div(self.max_boost as usize).unwrap_or(0).min(width);
+    write!(f, "[{:█<filled$}{:░<2$}]", "", "", width - filled)
+  }
+
+  fn interval(&self) -> u8 {
+    self.speed.saturating_sub(self.boost)
+  }
+
+  pub fn render(
+    &self,
+    f: &mut Frame,
+    arena: &Arena,
+    top: &mut Vec<ColoredPoint>,
+    bottom: &mut Vec<ColoredPoint>,
+    style: &CannibalStyle,
+    frame: usize,
+  ) -> fmt::Result {
     let cannibal = self.is_cannibal();
+    let color = if self.is_protected() && (frame / BLINK_FRAMES) % 2 == 1 {
+      PROTECTED_COLOR
+    } else if cannibal {
+      style.body.unwrap_or(self.color)
+    } else {
+      self.color
+    };
 
     for (i, p) in self.body.iter().enumerate() {
+      let wrapped = i > 0 && p.x.abs_diff(self.body[i - 1].x) + p.y.abs_diff(self.body[i - 1].y) > 1;
+      if wrapped && !self.straddle {
+        break;
+      }
+      if !arena.is_visible(p) {
+        continue;
+      }
       let is_top = p.y % 2 == 0;
+      let fill = if cannibal && i == 0 {
+        style.head_color(frame)
+      } else if self.gradient && i > 0 {
+        shade(color, 1. - GRADIENT_DIM * i as f32 / (self.len() - 1) as f32)
+      } else {
+        color
+      };
+
+      // Other glyphs can't share a cell with the other half so they take all of it
+      if let Some(glyph) = self.glyph {
+        fg(f, fill)?;
+        arena.draw(p, glyph, f)?;
+        reset(f)?;
+        continue;
+      }
 
       let v = if is_top { &mut *top } else { &mut *bottom };
-      if let Some(idx) = v.iter().position(|h| p == &h.point) {
-        let h = v.swap_remove(idx);
-        // This cursor position in the terminal has it's other half already filled so we set the
-        // background to the color of that other half to allow multiple colors along the y axis halves
-        bg(f, h.color)?;
+      let shared = if let Some(idx) = v.iter().position(|h| p == &h.point) {
+        Some(v.swap_remove(idx).color)
       } else {
-        let mut h = ColoredPoint {
-          point: *p,
-          color: self.color,
-        };
+        let mut h = ColoredPoint { point: *p, color: fill };
 
         if is_top {
           h.y += 1;
@@ -96,32 +545,199 @@ impl Snake {
 
         let v = if is_top { &mut *bottom } else { &mut *top };
         v.push(h);
-      }
+        None
+      };
 
-      if cannibal && i == self.head {
-        fg(f, 196)?;
-      } else {
-        fg(f, self.color)?;
+      // When both halves of the cell share a color it's drawn as one full block, otherwise the
+      // background takes the color of the other half so each half keeps its own color
+      let full = shared == Some(fill);
+      if let Some(other) = shared.filter(|_| !full) {
+        bg(f, other)?;
       }
+      fg(f, fill)?;
 
-      p.offset(&arena.position).render(if is_top { '▀' } else { '▄' }, f)?;
+      // Quarter blocks would leave the other half of a shared cell blank so those stay whole
+      let glyph = if full {
+        '█'
+      } else if self.smooth && shared.is_none() {
+        let prev = i.checked_sub(1).and_then(|j| Direction::between(p, &self.body[j]));
+        let next = self.body.get(i + 1).and_then(|n| Direction::between(p, n));
+        segment_glyph(prev, next, is_top)
+      } else {
+        segment_glyph(None, None, is_top)
+      };
+      arena.draw(p, glyph, f)?;
       reset(f)?;
     }
 
     reset(f)
   }
 
+  pub fn strategy(&self) -> Strategy {
+    self.strat
+  }
+
+  pub fn direction(&self) -> Direction {
+    self.dir
+  }
+
+  pub fn is_human(&self) -> bool {
+    matches!(self.strat, Strategy::Player)
+  }
+
+  // Whether a player has steered this snake since it spawned
+  pub fn took_control(&self) -> bool {
+    self.took_control
+  }
+
+  pub fn is_protected(&self) -> bool {
+    Instant::now() < self.spawn_protected_until
+  }
+
+  // Protected and god mode snakes pass through anything instead of crashing
+  fn is_invulnerable(&self) -> bool {
+    self.god_mode || self.is_protected()
+  }
+
+  pub fn is_alive(&self) -> bool {
+    self.alive
+  }
+
+  pub fn stats(&self) -> SnakeStats {
+    let current = if self.alive { self.alive_since.elapsed() } else { Duration::ZERO };
+    SnakeStats { survived: self.totals.survived + current, ..self.totals }
+  }
+
   pub fn len(&self) -> usize {
     self.body.len()
   }
 
-  pub fn serpentine(snakes: &mut [Snake], idx: usize, rng: &mut Rng, arena: &Arena) {
-    let (x, y) = snakes[idx].dir.coords();
-    let prev_head = snakes[idx].body[cycle_back(&snakes[idx].body, &mut snakes[idx].head)];
-    let mut head = snakes[idx].body[snakes[idx].head];
+  pub fn is_empty(&self) -> bool {
+    self.body.is_empty()
+  }
+
+  pub fn score(&self) -> usize {
+    let ScoreWeights { length, survival, kills } = self.score_weights;
+    let survived = if self.alive { self.alive_since.elapsed().as_secs() as usize } else { 0 };
+    (self.len() + self.bonus) * length + survived * survival + self.kills * kills
+  }
 
-    head.x = prev_head.x.wrapping_add_signed(x);
-    head.y = prev_head.y.wrapping_add_signed(y);
+  // New segments stack on the tail, which then stays put for that many moves while the body unfolds out of it.
+  // grow_behind relies on this
+  pub fn grow(&mut self, n: usize) {
+    let segments = self.max_len.map_or(n, |max| std::cmp::min(n, max.saturating_sub(self.len())));
+    let tail = *self.tail();
+    self.body.extend((0..segments).map(|_| tail));
+    self.bonus += n - segments;
+  }
+
+  // Grows like grow but lays the new segments out in a line behind the tail, away from the heading. Once the
+  // line would run into a wall or the body itself the rest stay stacked on the last segment laid out
+  pub fn grow_behind(&mut self, n: usize, arena: &Arena) {
+    let len = self.len();
+    self.grow(n);
+    let back = self.dir.inverse();
+    let mut tail = *self.tail();
+    for i in len..self.len() {
+      let (next, wall) = Self::next_head(&self.body[i - 1], back, arena);
+      if !wall && !self.body.range(..i).any(|p| *p == next) {
+        tail = next;
+      }
+      self.body[i] = tail;
+    }
+  }
+
+  pub fn serpentine(snakes: &mut [Snake], idx: usize, rng: &mut Rng, arena: &mut Arena) {
+    snakes[idx].turn();
+    Self::step(snakes, idx, rng, arena);
+  }
+
+  // Moves a single cell, returning whether the snake survived and kept going forward
+  pub fn step(snakes: &mut [Snake], idx: usize, rng: &mut Rng, arena: &mut Arena) -> bool {
+    let (head, wall) = Self::next_head(snakes[idx].head(), snakes[idx].dir, arena);
+    if wall && snakes[idx].alive && snakes[idx].rebound(arena.wall_policy) {
+      arena.sync(snakes, idx);
+      return false;
+    }
+
+    let mut killer = None;
+    let alive = snakes[idx].alive;
+    // Spawn protection doesn't cover walls, god mode goes through them
+    let wall = wall && !snakes[idx].god_mode;
+    let crashed = alive && (wall || !snakes[idx].is_invulnerable() && Self::is_crash(snakes, idx, &head, arena, &mut killer));
+    Self::settle(snakes, idx, head, crashed, killer, rng, arena);
+    alive && !crashed
+  }
+
+  // Moves every snake in `moving` at once, checking crashes against the state before anyone moved
+  pub fn serpentine_all(snakes: &mut [Snake], moving: &[usize], rng: &mut Rng, arena: &mut Arena) {
+    for &idx in moving {
+      snakes[idx].turn();
+    }
+    let (moving, rebounded): (Vec<usize>, Vec<usize>) = moving.iter().partition(|&&idx| {
+      let (_, wall) = Self::next_head(snakes[idx].head(), snakes[idx].dir, arena);
+      !(wall && snakes[idx].alive && snakes[idx].rebound(arena.wall_policy))
+    });
+    for idx in rebounded {
+      arena.sync(snakes, idx);
+    }
+    let mut moves: Box<[(Point, bool, Option<usize>)]> = moving
+      .iter()
+      .map(|&idx| {
+        let (head, wall) = Self::next_head(snakes[idx].head(), snakes[idx].dir, arena);
+        let (wall, mut killer) = (wall && !snakes[idx].god_mode, None);
+        let crashed = snakes[idx].alive && (wall || !snakes[idx].is_invulnerable() && Self::is_crash(snakes, idx, &head, arena, &mut killer));
+        (head, crashed, killer)
+      })
+      .collect();
+
+    // Head-to-head collisions have no winner
+    for a in 0..moves.len() {
+      for b in a + 1..moves.len() {
+        let rivals = snakes[moving[a]].team != snakes[moving[b]].team;
+        if moves[a].0 == moves[b].0 && rivals && snakes[moving[a]].alive && snakes[moving[b]].alive {
+          moves[a] = (moves[a].0, !snakes[moving[a]].is_invulnerable(), None);
+          moves[b] = (moves[b].0, !snakes[moving[b]].is_invulnerable(), None);
+        }
+      }
+    }
+
+    for (&idx, &(head, crashed, killer)) in moving.iter().zip(moves.iter()) {
+      Self::settle(snakes, idx, head, crashed, killer, rng, arena);
+    }
+  }
+
+  // Applies the arena's wall policy, returning false when the wall should kill the snake instead
+  fn rebound(&mut self, policy: WallPolicy) -> bool {
+    match policy {
+      WallPolicy::Kill => return false,
+      WallPolicy::Bounce => (),
+      WallPolicy::Penalty(n) => {
+        for _ in 0..n {
+          if !self.remove_tail() {
+            break;
+          }
+        }
+      }
+    }
+    self.reverse();
+    true
+  }
+
+  // Swaps head and tail so the snake heads back the way it came
+  fn reverse(&mut self) {
+    self.body.make_contiguous().reverse();
+    // The old tail may have been heading anywhere, a body still stacked on one cell just turns around
+    let dir = self.body.get(1).and_then(|neck| Direction::between(neck, self.head()));
+    self.dir = dir.unwrap_or(self.dir.inverse());
+    self.turns.clear();
+  }
+
+  fn next_head(prev_head: &Point, dir: Direction, arena: &Arena) -> (Point, bool) {
+    let (x, y) = dir.coords();
+    let mut head = Point::new(prev_head.x.wrapping_add_signed(x), prev_head.y.wrapping_add_signed(y));
+
+    let wall = arena.hits_wall(&head);
 
     if head.x == u8::MAX {
       head.x = arena.size.x - 1;
@@ -130,74 +746,309 @@ impl Snake {
     }
 
     if head.y == u8::MAX {
-      head.y = (arena.size.y << 1) - 1;
-    } else if head.y > (arena.size.y << 1) - 1 {
+      head.y = arena.height() - 1;
+    } else if head.y > arena.height() - 1 {
       head.y = 0;
     }
 
-    let mut killer = None;
-    if snakes[idx].alive && Self::is_crash(snakes, idx, &head, &mut killer) {
+    (head, wall)
+  }
+
+  fn settle(snakes: &mut [Snake], idx: usize, head: Point, crashed: bool, killer: Option<usize>, rng: &mut Rng, arena: &mut Arena) {
+    if crashed {
       snakes[idx].alive = false;
-      snakes[idx].cannibal = Instant::now() - Duration::from_secs(EFFECT_SECONDS);
-      snakes[idx].speed = 80;
+      snakes[idx].end_effects();
+      snakes[idx].speed = RESPAWN_SPEED.saturating_sub(snakes[idx].ramped);
+      snakes[idx].boost = 0;
+      snakes[idx].recent.clear();
+      snakes[idx].turns.clear();
+      snakes[idx].bonus = 0;
+      snakes[idx].kills = 0;
+      snakes[idx].shrink_from = (Instant::now(), snakes[idx].len());
+      snakes[idx].totals.survived += snakes[idx].alive_since.elapsed();
+      Self::resolve_death(snakes, idx, killer);
       if let Some(i) = killer {
-        let point = *snakes[i].head();
-        let score = snakes[idx].len();
-        snakes[i].body.extend((0..score).map(|_| point));
+        arena.sync(snakes, i);
       }
     }
 
     if snakes[idx].alive {
-      *snakes[idx].head_mut() = head;
-    } else if !snakes[idx].remove_tail() {
+      snakes[idx].body.push_front(head);
+      // Light cycles leave their whole trail behind as a wall
+      if !snakes[idx].tron {
+        snakes[idx].body.pop_back();
+      }
+      if snakes[idx].self_collision == SelfCollision::Truncate {
+        if let Some(bitten) = snakes[idx].body.iter().skip(1).position(|p| *p == head) {
+          snakes[idx].body.truncate(bitten + 1);
+        }
+      }
+      snakes[idx].recent.push_front(head);
+      snakes[idx].recent.truncate(MEMORY);
+    } else if !snakes[idx].tron && !snakes[idx].shrink() {
       snakes[idx].alive = true;
-      snakes[idx].head_mut().randomize(rng, &arena.size)
+      snakes[idx].spawn_protected_until = Instant::now() + snakes[idx].spawn_protection;
+      snakes[idx].alive_since = Instant::now();
+      snakes[idx].respawn(rng, arena);
     }
+    arena.sync(snakes, idx);
+  }
+
+  // Kills the snake where it is as if it had crashed without a killer
+  pub fn kill(snakes: &mut [Snake], idx: usize, rng: &mut Rng, arena: &mut Arena) {
+    let head = *snakes[idx].head();
+    Self::settle(snakes, idx, head, true, None, rng, arena);
+  }
+
+  // Counts a death and rewards the killer, if any, following the victim's kill policy
+  pub fn resolve_death(snakes: &mut [Snake], victim: usize, killer: Option<usize>) {
+    let policy = snakes[victim].kill_policy;
+    let killer = killer.filter(|&i| i != victim && (policy.team_kills || snakes[i].team != snakes[victim].team));
+    if killer.is_some() || policy.self_deaths {
+      snakes[victim].totals.deaths += 1;
+    }
+    if let Some(i) = killer {
+      let score = snakes[victim].len();
+      snakes[i].grow(score);
+      snakes[i].kills += 1;
+      snakes[i].totals.kills += 1;
+    }
+  }
+
+  // Moves the head somewhere in the spawn region, or anywhere in the arena without one. The player is idle
+  // again until they steer the new snake
+  pub fn respawn(&mut self, rng: &mut Rng, arena: &Arena) {
+    self.took_control = false;
+    match self.spawn_region {
+      Some(region) => {
+        let region = region.clamp(&Point::new(arena.size.x, arena.height()));
+        self.head_mut().randomize_in(rng, &region);
+      }
+      None => self.head_mut().randomize(rng, &arena.size),
+    }
+  }
+
+  pub fn dash_charges(&self) -> u8 {
+    let recharged = match self.dash.recharge.as_nanos() {
+      0 => u8::MAX,
+      recharge => std::cmp::min(self.dash_recharged_at.elapsed().as_nanos() / recharge, u8::MAX as u128) as u8,
+    };
+    std::cmp::min(self.dash_charges.saturating_add(recharged), self.dash.charges)
+  }
+
+  pub fn refill_dash(&mut self) {
+    self.dash_charges = self.dash.charges;
+    self.dash_recharged_at = Instant::now();
+  }
+
+  // Spends a charge so the next move covers the whole dash distance
+  pub fn dash(&mut self) -> bool {
+    let charges = self.dash_charges();
+    if !self.alive || charges == 0 {
+      return false;
+    }
+    if charges == self.dash.charges {
+      self.dash_recharged_at = Instant::now();
+    } else {
+      self.dash_recharged_at += self.dash.recharge * charges.saturating_sub(self.dash_charges) as u32;
+    }
+    self.dash_charges = charges - 1;
+    self.dashing = true;
+    true
+  }
+
+  // Cells the coming move covers, more than one right after a dash
+  pub fn take_dash(&mut self) -> u8 {
+    if std::mem::take(&mut self.dashing) && self.alive {
+      self.dash.distance.max(1)
+    } else {
+      1
+    }
+  }
+
+  // Sheds the tail of a dead snake, returning false once it's down to the length it respawns at
+  fn shrink(&mut self) -> bool {
+    const MIN_LEN: usize = 3;
+    if self.len() <= MIN_LEN {
+      return false;
+    }
+    let len = match self.death_shrink {
+      DeathShrink::PerMove(n) => self.len().saturating_sub(n.max(1) as usize),
+      DeathShrink::Within(duration) => {
+        let (died_at, len) = self.shrink_from;
+        let left = 1. - (died_at.elapsed().as_secs_f32() / duration.as_secs_f32()).min(1.);
+        MIN_LEN + (len.saturating_sub(MIN_LEN) as f32 * left) as usize
+      }
+    };
+    self.body.truncate(len.max(MIN_LEN));
+    true
   }
 
   pub fn remove_tail(&mut self) -> bool {
     if self.len() > 3 {
-      self.body.remove(self.tail_idx());
-      cycle_back(&self.body, &mut self.head);
+      self.body.pop_back();
       true
     } else {
       false
     }
   }
 
-  pub fn eat(snakes: &mut [Snake], idx: usize, rng: &mut Rng, food: &mut [Food], arena: &Arena) {
-    for food in food {
-      if *snakes[idx].head() == food.position {
-        food.apply_effect(&mut snakes[idx]);
+  pub fn eat(
+    snakes: &mut [Snake],
+    idx: usize,
+    rng: &mut Rng,
+    food: &mut [Food],
+    arena: &mut Arena,
+    bite_cooldown: Duration,
+    contest: FoodContest,
+  ) -> Vec<Food> {
+    let head = *snakes[idx].head();
+    let mut eaten = Vec::new();
+    let rivals: Vec<usize> = (0..snakes.len()).filter(|&i| i != idx && snakes[i].alive && *snakes[i].head() == head).collect();
+    let on_head = |food: &Food| food.position == head && !food.is_rotten();
+    if !rivals.is_empty() && contest == FoodContest::Contested {
+      for food in food.iter_mut().filter(|food| on_head(food)) {
         food.position.randomize(rng, &arena.size);
-        return;
+        food.spawned_at = Instant::now();
+      }
+      return eaten;
+    }
+    // Swapping first so the eater grows in its new place
+    if food.iter().any(|food| on_head(food) && food.effect() == Effect::Swap) {
+      Self::swap_places(snakes, idx, rng, arena);
+    }
+    for food in food.iter_mut().filter(|food| on_head(food)) {
+      eaten.push(food.clone());
+      if rivals.is_empty() || contest == FoodContest::FirstCome {
+        food.apply_effect(&mut snakes[idx]);
+      } else {
+        for i in std::iter::once(idx).chain(rivals.iter().copied()) {
+          let len = snakes[i].len();
+          food.apply_effect(&mut snakes[i]);
+          let grown = snakes[i].len().saturating_sub(len);
+          snakes[i].body.truncate(len + grown.div_ceil(2));
+        }
+      }
+      food.position.randomize(rng, &arena.size);
+      food.spawned_at = Instant::now();
+    }
+    if !eaten.is_empty() {
+      for i in std::iter::once(idx).chain(rivals) {
+        arena.sync(snakes, i);
       }
+      return eaten;
     }
 
-    if snakes[idx].is_cannibal() {
+    if snakes[idx].is_cannibal() && snakes[idx].bite.is_none_or(|at| at.elapsed() >= bite_cooldown) {
       for i in 0..snakes.len() {
-        if idx == i {
+        if idx == i || snakes[idx].team == snakes[i].team || snakes[i].god_mode {
           continue;
         }
 
         if *snakes[idx].head() == *snakes[i].tail() && snakes[i].remove_tail() {
-          snakes[idx].body.push(*snakes[idx].head());
+          snakes[idx].grow(1);
+          arena.sync(snakes, i);
+          arena.sync(snakes, idx);
           snakes[idx].cannibal = Instant::now();
-          return;
+          snakes[idx].bite = Some(Instant::now());
+          break;
         }
       }
     }
+
+    eaten
+  }
+
+  // Trades heads with the nearest living rival outside the team while both bodies stay where they are. Each
+  // head takes the other's heading so it leads away from the neck it lands next to, and crashes if whatever
+  // is left on its new cell is lethal
+  pub fn swap_places(snakes: &mut [Snake], idx: usize, rng: &mut Rng, arena: &mut Arena) -> Option<usize> {
+    let (head, team) = (*snakes[idx].head(), snakes[idx].team);
+    let rival = (0..snakes.len())
+      .filter(|&i| i != idx && snakes[i].alive && snakes[i].team != team && !snakes[i].body.is_empty())
+      .min_by_key(|&i| head.quick_distance(snakes[i].head()))?;
+
+    // Both heads leave before checking so neither one counts as an obstacle for the other
+    let heads = [snakes[idx].body.pop_front().unwrap(), snakes[rival].body.pop_front().unwrap()];
+    arena.sync(snakes, idx);
+    arena.sync(snakes, rival);
+    let mut crashes = [(idx, heads[1], false, None), (rival, heads[0], false, None)];
+    for (i, head, crashed, killer) in &mut crashes {
+      *crashed = !snakes[*i].is_invulnerable() && Self::is_crash(snakes, *i, head, arena, killer);
+    }
+
+    snakes[idx].body.push_front(heads[1]);
+    snakes[rival].body.push_front(heads[0]);
+    (snakes[idx].dir, snakes[rival].dir) = (snakes[rival].dir, snakes[idx].dir);
+    for (i, head, crashed, killer) in crashes {
+      snakes[i].recent.clear();
+      snakes[i].turns.clear();
+      if crashed {
+        Self::settle(snakes, i, head, true, killer, rng, arena);
+      } else {
+        arena.sync(snakes, i);
+      }
+    }
+    Some(rival)
   }
 
+  // Queues a turn for the coming moves so quick presses between two moves can't fold the snake onto its neck
   pub fn steer(&mut self, dir: Direction) {
-    self.dir = if self.dir.inverse() == dir { self.dir } else { dir };
+    self.took_control = true;
+    if !self.can_turn() {
+      return;
+    }
+    // Rate limited snakes take a single turn per move, the latest press against the current direction wins
+    if self.rate_limited {
+      if dir != self.dir && dir != self.dir.inverse() {
+        self.turns.clear();
+        self.turns.push_back(dir);
+      }
+      return;
+    }
+    let last = self.turns.back().copied().unwrap_or(self.dir);
+    if dir != last && dir != last.inverse() && self.turns.len() < MAX_TURNS {
+      self.turns.push_back(dir);
+    }
+  }
+
+  // Sets the direction outright while nothing moves, only refusing to double back over the neck
+  pub fn aim(&mut self, dir: Direction) {
+    self.took_control = true;
+    self.turns.clear();
+    let neck = self.body.get(1).and_then(|neck| Direction::between(neck, self.head()));
+    if neck != Some(dir.inverse()) {
+      self.dir = dir;
+    }
+  }
+
+  fn turn(&mut self) {
+    if !self.can_turn() {
+      self.turns.clear();
+    } else if let Some(dir) = self.turns.pop_front() {
+      self.dir = dir;
+      self.last_turn_tick = Some(self.moves);
+    }
+    self.moves = self.moves.wrapping_add(1);
+  }
+
+  // Whether the coming move may change direction, with a cooldown of k a snake turns at most every k moves
+  fn can_turn(&self) -> bool {
+    self.last_turn_tick.is_none_or(|tick| self.moves.wrapping_sub(tick) >= self.turn_cooldown as usize)
   }
 
   pub fn find_target(&self, snakes: &[Snake], food: &[Food]) -> Point {
-    if !matches!(self.strat, Strategy::Player) && self.is_cannibal() {
+    self.target_as(self.strat, snakes, food)
+  }
+
+  // Where this snake would head if it followed `strat`, which lets a player's snake be auto-piloted
+  pub fn target_as(&self, strat: Strategy, snakes: &[Snake], food: &[Food]) -> Point {
+    if !matches!(strat, Strategy::Player) && self.is_cannibal() {
       if let Some(target) = snakes
         .iter()
-        .filter(|&snake| !std::ptr::addr_eq(self, snake) && self.speed + 4 < snake.speed && snake.len() > 3)
+        .filter(|&snake| {
+          snake.team != self.team && self.interval() + self.strategy_params.flee_margin < snake.interval() && snake.len() > self.strategy_params.min_target_len
+        })
         .map(|snake| snake.tail())
         .min_by_key(|tail| self.tail().quick_distance(tail))
         .copied()
@@ -206,57 +1057,281 @@ impl Snake {
       }
     }
 
-    match self.strat {
+    match strat {
       Strategy::Player => unreachable!("Player has it's own mind"),
       Strategy::Speed => locate_food(food, self.head(), Effect::Speed),
-      Strategy::Score => locate_food(food, self.head(), Effect::Nourish),
-      Strategy::Eat => food
-        .iter()
-        .min_by_key(|food| self.head().quick_distance(food))
-        .map(|food| food.position)
-        .unwrap(),
-      Strategy::Kill => {
-        if let Some(target) = snakes
-          .iter()
-          .filter(|&snake| !std::ptr::addr_eq(self, snake) && self.speed + 10 < snake.speed)
-          .max_by_key(|snake| snake.len())
-          .map(|snake| *snake.head())
-        {
-          target
-        } else {
-          locate_food(food, self.head(), Effect::Speed)
-        }
-      }
+      Strategy::Score => self.score_target(snakes, food),
+      Strategy::Eat => self.nearest_food(food),
+      Strategy::Kill => self.kill_target(snakes, food),
       Strategy::Cannibal => locate_food(food, self.head(), if self.is_cannibal() { Effect::Speed } else { Effect::Cannibal }),
+      Strategy::Adaptive => self.adaptive_target(snakes, food),
+      Strategy::Weighted { food: w_food, kill, safety } => self.weighted_target(snakes, food, w_food, kill, safety),
+      Strategy::Pack => self.pack_target(snakes, food, |_| false),
+    }
+  }
+
+  // Like find_target but pack snakes split the prey's escape routes between them through the plan
+  pub fn coordinated_target(snakes: &[Snake], idx: usize, food: &[Food], plan: &mut PackPlan) -> Point {
+    let snake = &snakes[idx];
+    if snake.strat != Strategy::Pack || snake.is_cannibal() {
+      return snake.find_target(snakes, food);
+    }
+    let taken = |p: &Point| (0..snakes.len()).any(|i| i != idx && snakes[i].alive && plan.claimed(i) == Some(*p));
+    let target = snake.pack_target(snakes, food, taken);
+    plan.claim(idx, target);
+    target
+  }
+
+  // The nearest free cell cutting off the closest player, or food when there's no player to hunt
+  fn pack_target(&self, snakes: &[Snake], food: &[Food], taken: impl Fn(&Point) -> bool) -> Point {
+    let head = self.head();
+    let Some(prey) = snakes
+      .iter()
+      .filter(|snake| snake.is_human() && snake.alive && snake.team != self.team)
+      .min_by_key(|snake| head.quick_distance(snake.head()))
+    else {
+      return self.nearest_food(food);
+    };
+    let intercepts = prey.intercepts();
+    intercepts
+      .iter()
+      .filter(|p| !taken(p))
+      .min_by_key(|p| head.quick_distance(p))
+      .copied()
+      .unwrap_or(intercepts[0])
+  }
+
+  // Cells cutting off where the snake is heading, straight ahead of it and to either side
+  fn intercepts(&self) -> [Point; 3] {
+    let (dx, dy) = self.dir.coords();
+    let (dx, dy) = (dx as i32, dy as i32);
+    let ahead = *self.head() + (dx * PACK_LEAD, dy * PACK_LEAD);
+    let side = *self.head() + (dx * PACK_LEAD / 2, dy * PACK_LEAD / 2);
+    [ahead, side + (-dy * PACK_SPREAD, dx * PACK_SPREAD), side + (dy * PACK_SPREAD, -dx * PACK_SPREAD)]
+  }
+
+  fn weighted_target(&self, snakes: &[Snake], food: &[Food], w_food: f32, w_kill: f32, w_safety: f32) -> Point {
+    const SAFETY_RANGE: f32 = 64.;
+    let head = self.head();
+    let rivals = || snakes.iter().filter(|&snake| !std::ptr::addr_eq(self, snake));
+    // Distance to the closest rival head, capped so open space stops mattering past a point
+    let safety = |p: &Point| rivals().map(|snake| snake.head().quick_distance(p)).min().map_or(SAFETY_RANGE, |d| (d as f32).min(SAFETY_RANGE)) / SAFETY_RANGE;
+    let closeness = |p: &Point| 1. / (1 + head.quick_distance(p)) as f32;
+
+    let food_targets = food.iter().map(|food| (food.position, w_food * closeness(&food.position)));
+    let kill_targets = rivals()
+      .filter(|snake| snake.team != self.team && self.interval() + self.strategy_params.kill_margin < snake.interval())
+      .map(|snake| (*snake.head(), w_kill * closeness(snake.head()) * snake.len() as f32 / self.len() as f32));
+
+    food_targets
+      .chain(kill_targets)
+      .map(|(p, score)| (p, score + w_safety * safety(&p)))
+      .max_by(|(_, a), (_, b)| a.total_cmp(b))
+      .map(|(p, _)| p)
+      .unwrap_or_else(|| self.nearest_food(food))
+  }
+
+  // Whatever adds the most score per cell travelled, either nourishing food or a kill it's fast enough for
+  fn score_target(&self, snakes: &[Snake], food: &[Food]) -> Point {
+    let ScoreWeights { length, kills, .. } = self.score_weights;
+    let head = self.head();
+    let food_targets = food.iter().filter(|food| food.effect() == Effect::Nourish).map(|food| (food.position, 2 * length));
+    let kill_targets = snakes
+      .iter()
+      .filter(|snake| snake.team != self.team && self.interval() + self.strategy_params.kill_margin < snake.interval())
+      .map(|snake| (*snake.head(), kills + snake.len() * length));
+
+    food_targets
+      .chain(kill_targets)
+      .map(|(p, value)| (p, value as f32 / (1 + head.quick_distance(&p)) as f32))
+      .max_by(|(_, a), (_, b)| a.total_cmp(b))
+      .map(|(p, _)| p)
+      .unwrap_or_else(|| self.nearest_food(food))
+  }
+
+  fn nearest_food(&self, food: &[Food]) -> Point {
+    food
+      .iter()
+      .min_by_key(|food| self.head().quick_distance(food))
+      .map(|food| food.position)
+      .unwrap()
+  }
+
+  fn kill_target(&self, snakes: &[Snake], food: &[Food]) -> Point {
+    if let Some(target) = snakes
+      .iter()
+      .filter(|&snake| snake.team != self.team && self.interval() + self.strategy_params.kill_margin < snake.interval())
+      .max_by_key(|snake| snake.len())
+      .map(|snake| *snake.head())
+    {
+      target
+    } else {
+      locate_food(food, self.head(), Effect::Speed)
+    }
+  }
+
+  fn adaptive_target(&self, snakes: &[Snake], food: &[Food]) -> Point {
+    const HUNT_LEN: usize = 16;
+    const HUNT_SPEED: u8 = 45;
+
+    let head = self.head();
+    if let Some(threat) = snakes
+      .iter()
+      .filter(|&snake| !std::ptr::addr_eq(self, snake) && snake.is_cannibal() && snake.interval() < self.interval())
+      .map(|snake| snake.head())
+      .filter(|threat| head.quick_distance(threat) < self.strategy_params.aggro_radius)
+      .min_by_key(|threat| head.quick_distance(threat))
+    {
+      // Run directly away from the threat
+      return *head + (head.x as i32 - threat.x as i32, head.y as i32 - threat.y as i32);
+    }
+
+    if self.len() >= HUNT_LEN && self.interval() <= HUNT_SPEED {
+      self.kill_target(snakes, food)
+    } else {
+      self.nearest_food(food)
     }
   }
 
   pub fn is_cannibal(&self) -> bool {
-    self.cannibal.elapsed().as_secs() < EFFECT_SECONDS
+    Instant::now() < Self::effect_end(self.cannibal, self.cannibal_until)
   }
 
-  pub fn seek(snakes: &mut [Snake], idx: usize, target: &Point, bounds: &Point) {
-    for nearest in snakes[idx].head().nearest_directions(target, bounds) {
-      if nearest == snakes[idx].dir.inverse() {
-        continue;
+  pub fn can_turn_cannibal(&self) -> bool {
+    self.len() >= self.cannibal_min_len
+  }
+
+  pub fn is_magnetized(&self) -> bool {
+    Instant::now() < Self::effect_end(self.magnet, self.magnet_until)
+  }
+
+  // Drags the nearest food a cell toward a magnetized head unless another magnet is closer to it
+  pub fn pull_food(snakes: &[Snake], idx: usize, food: &mut [Food]) {
+    let snake = &snakes[idx];
+    if !snake.alive || !snake.is_magnetized() {
+      return;
+    }
+    let head = *snake.head();
+    let Some(nearest) = food.iter_mut().filter(|food| food.effect() != Effect::Magnet && !food.is_rotten()).min_by_key(|food| head.quick_distance(food)) else {
+      return;
+    };
+    let distance = head.quick_distance(nearest);
+    let contested = snakes.iter().enumerate().any(|(i, other)| {
+      let other_distance = other.head().quick_distance(nearest);
+      i != idx && other.alive && other.is_magnetized() && (other_distance < distance || other_distance == distance && i < idx)
+    });
+    let next = nearest.position.step_toward(&head);
+    if contested || distance <= 1 || is_occupied(&next, snakes) {
+      return;
+    }
+    nearest.pull(next);
+  }
+
+  pub fn seek(snakes: &mut [Snake], idx: usize, target: &Point, arena: &Arena) {
+    let head = *snakes[idx].head();
+    let safe: Box<[Direction]> = head
+      .nearest_directions(target, &arena.size)
+      .into_iter()
+      .filter(|&nearest| {
+        let next_head = head + nearest.coords();
+        nearest != snakes[idx].dir.inverse() && !arena.hits_wall(&next_head) && !Self::is_crash(snakes, idx, &next_head, arena, &mut None)
+      })
+      .collect();
+
+    // Recently visited cells are only entered again when nothing else is safe, which breaks loops around obstacles
+    let snake = &snakes[idx];
+    if let Some(&dir) = safe.iter().find(|dir| !snake.recent.contains(&(head + dir.coords()))).or(safe.first()) {
+      if dir != snake.dir && snake.can_turn() {
+        snakes[idx].dir = dir;
+        snakes[idx].last_turn_tick = Some(snakes[idx].moves);
       }
-      let next_head = *snakes[idx].head() + nearest.coords();
-      if !Self::is_crash(snakes, idx, &next_head, &mut None) {
-        snakes[idx].dir = nearest;
-        break;
+    }
+  }
+
+  // Cuts off the back half of a snake as a new snake heading away from the cut, with its own team and
+  // color. Snakes too short for two halves of MIN_SPLIT_LEN don't split and neither do they once every
+  // color is taken
+  pub fn split(snakes: &mut Vec<Snake>, idx: usize, strat: Strategy, rng: &mut Rng, arena: &mut Arena) -> Option<usize> {
+    let len = snakes[idx].len();
+    if !snakes[idx].alive || len < MIN_SPLIT_LEN * 2 || snakes.len() >= SNAKE_COLORS.len() {
+      return None;
+    }
+    let back: VecDeque<Point> = snakes[idx].body.split_off(len - len / 2).into_iter().rev().collect();
+    let parent = &snakes[idx];
+
+    let mut snake = Snake::random(1, strat, None, rng, &arena.size);
+    snake.dir = Direction::between(&back[1], &back[0]).unwrap_or(parent.dir.inverse());
+    snake.body = back;
+    snake.max_len = parent.max_len;
+    snake.max_boost = parent.max_boost;
+    snake.score_weights = parent.score_weights;
+    snake.strategy_params = parent.strategy_params;
+    snake.smooth = parent.smooth;
+    snake.gradient = parent.gradient;
+    snake.straddle = parent.straddle;
+    snake.glyph = parent.glyph.map(|_| strat.glyph());
+    snake.cannibal_min_len = parent.cannibal_min_len;
+    snake.turn_cooldown = parent.turn_cooldown;
+    snake.self_collision = parent.self_collision;
+    snake.other_collision = parent.other_collision;
+    snake.reaction = parent.reaction;
+    snake.death_shrink = parent.death_shrink;
+    snake.stacking = parent.stacking;
+    snake.kill_policy = parent.kill_policy;
+    // Ids are searched upward from the parent's since assigned teams count down from u8::MAX, the split snake only
+    // stays on its parent's team if all 256 are taken
+    snake.team = (1..=u8::MAX)
+      .map(|offset| parent.team.wrapping_add(offset))
+      .find(|&team| snakes.iter().all(|snake| snake.team != team))
+      .unwrap_or(parent.team);
+    snakes.push(snake);
+    Self::assign_colors(snakes);
+    arena.sync(snakes, idx);
+    arena.sync(snakes, snakes.len() - 1);
+    Some(snakes.len() - 1)
+  }
+
+  pub fn assign_colors(snakes: &mut [Snake]) {
+    let mut used = Vec::with_capacity(snakes.len());
+    let mut duplicates = Vec::new();
+    for (i, snake) in snakes.iter().enumerate() {
+      if used.contains(&snake.color) {
+        duplicates.push(i);
+      } else {
+        used.push(snake.color);
+      }
+    }
+
+    for i in duplicates {
+      if let Some(&color) = SNAKE_COLORS.iter().find(|color| !used.contains(*color)) {
+        snakes[i].color = color;
+        used.push(color);
       }
     }
   }
 
-  pub fn is_crash(snakes: &[Snake], idx: usize, head: &Point, killer: &mut Option<usize>) -> bool {
+  pub fn is_crash(snakes: &[Snake], idx: usize, head: &Point, arena: &Arena, killer: &mut Option<usize>) -> bool {
+    if arena.rotten.contains(head) {
+      return true;
+    }
     let cannibal = snakes[idx].is_cannibal();
+    if let Some(occupancy) = &arena.occupancy {
+      return Self::is_crash_at(snakes, idx, head, occupancy, killer);
+    }
 
     let ret = snakes.iter().enumerate().any(|(i, snake)| {
+      // Teammates pass through each other
+      if idx != i && (snake.team == snakes[idx].team || snakes[idx].other_collision == OtherCollision::Ignore) {
+        return false;
+      }
+      if idx == i && snakes[idx].self_collision != SelfCollision::Lethal {
+        return false;
+      }
       let crashed = snake
         .body
         .iter()
         .enumerate()
-        .any(|(i, p)| !(cannibal && i == snake.tail_idx()) && p == head);
+        .any(|(i, p)| !(cannibal && !snake.tron && i == snake.len() - 1) && p == head);
       if crashed && idx != i {
         *killer = Some(i);
       }
@@ -265,4 +1340,904 @@ impl Snake {
 
     ret
   }
+
+  // Same as the scan in is_crash but only looking at the snakes on the head's cell. A cannibal still
+  // passes over a tail as long as no other segment of that snake shares its cell.
+  fn is_crash_at(snakes: &[Snake], idx: usize, head: &Point, occupancy: &Occupancy, killer: &mut Option<usize>) -> bool {
+    let cannibal = snakes[idx].is_cannibal();
+    let hit = occupancy.occupants(head).iter().find(|&&(i, segments)| {
+      let snake = &snakes[i];
+      if idx != i && (snake.team == snakes[idx].team || snakes[idx].other_collision == OtherCollision::Ignore) {
+        return false;
+      }
+      if idx == i && snakes[idx].self_collision != SelfCollision::Lethal {
+        return false;
+      }
+      !(cannibal && !snake.tron && segments == 1 && snake.tail() == head)
+    });
+    if let Some(&(i, _)) = hit.filter(|&&(i, _)| i != idx) {
+      *killer = Some(i);
+    }
+    hit.is_some()
+  }
+}
+
+// Turning segments render as the quarter block on the side of their horizontal neighbor
+fn segment_glyph(prev: Option<Direction>, next: Option<Direction>, is_top: bool) -> char {
+  let side = match (prev, next) {
+    (Some(Direction::Left | Direction::Right), Some(Direction::Left | Direction::Right)) => None,
+    (Some(side @ (Direction::Left | Direction::Right)), Some(_)) | (Some(_), Some(side @ (Direction::Left | Direction::Right))) => Some(side),
+    _ => None,
+  };
+  match (side, is_top) {
+    (Some(Direction::Left), true) => '▘',
+    (Some(Direction::Right), true) => '▝',
+    (Some(Direction::Left), false) => '▖',
+    (Some(Direction::Right), false) => '▗',
+    (_, true) => '▀',
+    (_, false) => '▄',
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn snake(strat: Strategy, p: Point, speed: u8) -> Snake {
+    let mut snake = Snake::random(8, strat, None, &mut Rng::seeded(1), &Point::new(32, 32));
+    snake.body = VecDeque::from(vec![p; 8]);
+    snake.speed = speed;
+    snake
+  }
+
+  // Snakes and food at fixed positions, each snake on its own team
+  #[derive(Default)]
+  struct Scenario {
+    snakes: Vec<Snake>,
+    food: Vec<Food>,
+  }
+
+  impl Scenario {
+    fn snake(mut self, strat: Strategy, head: Point, body: &[Point]) -> Self {
+      let mut snake = snake(strat, head, 55);
+      snake.body = std::iter::once(head).chain(body.iter().copied()).collect();
+      snake.team = self.snakes.len() as u8;
+      self.snakes.push(snake);
+      self
+    }
+
+    fn speed(mut self, speed: u8) -> Self {
+      self.snakes.last_mut().unwrap().speed = speed;
+      self
+    }
+
+    fn cannibal(mut self) -> Self {
+      self.snakes.last_mut().unwrap().cannibal = Instant::now();
+      self
+    }
+
+    fn magnetized(mut self) -> Self {
+      self.snakes.last_mut().unwrap().magnet = Instant::now();
+      self
+    }
+
+    fn food(mut self, effect: Effect, position: Point) -> Self {
+      self.food.push(Food::new(effect, position));
+      self
+    }
+
+    fn target(&self) -> Point {
+      self.snakes[0].find_target(&self.snakes, &self.food)
+    }
+  }
+
+  fn line(from: Point, len: u8) -> Vec<Point> {
+    (1..=len).map(|i| Point::new(from.x, from.y + i)).collect()
+  }
+
+  #[test]
+  fn speed_strategy_seeks_speed_food() {
+    let head = Point::new(10, 10);
+    let scenario = Scenario::default()
+      .snake(Strategy::Speed, head, &line(head, 3))
+      .food(Effect::None, Point::new(11, 10))
+      .food(Effect::Speed, Point::new(20, 10))
+      .food(Effect::Speed, Point::new(10, 40));
+    assert_eq!(scenario.target(), Point::new(20, 10));
+  }
+
+  #[test]
+  fn score_strategy_seeks_nourishing_food() {
+    let head = Point::new(10, 10);
+    let scenario = Scenario::default()
+      .snake(Strategy::Score, head, &line(head, 3))
+      .food(Effect::Speed, Point::new(11, 10))
+      .food(Effect::Nourish, Point::new(4, 2))
+      .food(Effect::Nourish, Point::new(10, 30));
+    assert_eq!(scenario.target(), Point::new(4, 2));
+  }
+
+  #[test]
+  fn eat_strategy_seeks_nearest_food() {
+    let head = Point::new(10, 10);
+    let scenario = Scenario::default()
+      .snake(Strategy::Eat, head, &line(head, 3))
+      .food(Effect::Nourish, Point::new(20, 20))
+      .food(Effect::Cannibal, Point::new(8, 11))
+      .food(Effect::Speed, Point::new(10, 5));
+    assert_eq!(scenario.target(), Point::new(8, 11));
+  }
+
+  #[test]
+  fn kill_strategy_hunts_the_longest_slower_snake() {
+    let (head, short, long, fast) = (Point::new(10, 10), Point::new(2, 2), Point::new(30, 2), Point::new(12, 10));
+    let scenario = Scenario::default()
+      .snake(Strategy::Kill, head, &line(head, 3))
+      .speed(40)
+      .snake(Strategy::Eat, short, &line(short, 3))
+      .speed(60)
+      .snake(Strategy::Eat, long, &line(long, 9))
+      .speed(60)
+      .snake(Strategy::Eat, fast, &line(fast, 12))
+      .speed(45)
+      .food(Effect::Speed, Point::new(11, 10));
+    assert_eq!(scenario.target(), long);
+  }
+
+  #[test]
+  fn kill_margin_decides_which_rivals_are_hunted() {
+    let (head, near, far) = (Point::new(10, 10), Point::new(2, 2), Point::new(30, 2));
+    let mut scenario = Scenario::default()
+      .snake(Strategy::Kill, head, &line(head, 3))
+      .speed(40)
+      .snake(Strategy::Eat, near, &line(near, 3))
+      .speed(60)
+      .snake(Strategy::Eat, far, &line(far, 9))
+      .speed(48)
+      .food(Effect::Speed, Point::new(11, 10));
+    assert_eq!(scenario.target(), near);
+
+    scenario.snakes[0].strategy_params.kill_margin = 4;
+    assert_eq!(scenario.target(), far);
+    scenario.snakes[0].strategy_params.kill_margin = 30;
+    assert_eq!(scenario.target(), Point::new(11, 10));
+  }
+
+  #[test]
+  fn cannibal_strategy_seeks_cannibal_food() {
+    let head = Point::new(10, 10);
+    let scenario = Scenario::default()
+      .snake(Strategy::Cannibal, head, &line(head, 3))
+      .food(Effect::Speed, Point::new(11, 10))
+      .food(Effect::Cannibal, Point::new(25, 10));
+    assert_eq!(scenario.target(), Point::new(25, 10));
+  }
+
+  #[test]
+  fn adaptive_strategy_runs_from_faster_cannibals() {
+    let (head, threat) = (Point::new(10, 10), Point::new(13, 8));
+    let scenario = Scenario::default()
+      .snake(Strategy::Adaptive, head, &line(head, 3))
+      .snake(Strategy::Cannibal, threat, &line(threat, 3))
+      .speed(40)
+      .cannibal()
+      .food(Effect::None, Point::new(14, 8));
+    assert_eq!(scenario.target(), Point::new(7, 12));
+  }
+
+  #[test]
+  fn adaptive_strategy_eats_when_short_and_hunts_when_long() {
+    let (head, prey) = (Point::new(10, 10), Point::new(20, 4));
+    let adaptive = |len| {
+      Scenario::default()
+        .snake(Strategy::Adaptive, head, &line(head, len))
+        .speed(40)
+        .snake(Strategy::Eat, prey, &line(prey, 3))
+        .speed(60)
+        .food(Effect::None, Point::new(12, 10))
+    };
+    assert_eq!(adaptive(3).target(), Point::new(12, 10));
+    assert_eq!(adaptive(20).target(), prey);
+  }
+
+  #[test]
+  fn weighted_strategy_follows_its_weights() {
+    let food = [Food::new(Effect::None, Point::new(10, 14))];
+    let hunter = |weights| {
+      let mut snakes = [snake(weights, Point::new(10, 10), 40), snake(Strategy::Eat, Point::new(14, 10), 60)];
+      snakes[1].team = 1;
+      snakes
+    };
+
+    let snakes = hunter(Strategy::weighted(0., 1., 0.));
+    assert_eq!(snakes[0].find_target(&snakes, &food), Point::new(14, 10));
+
+    let snakes = hunter(Strategy::weighted(1., 0., 0.));
+    assert_eq!(snakes[0].find_target(&snakes, &food), Point::new(10, 14));
+  }
+
+  #[test]
+  fn vertical_segments_sharing_a_cell_render_as_a_full_block() {
+    let mut snake = snake(Strategy::Eat, Point::new(4, 4), 55);
+    snake.body = VecDeque::from([Point::new(4, 4), Point::new(4, 5)]);
+    let arena = Arena::new(0, 0, 40, 20);
+    let (mut f, mut top, mut bottom) = (Frame::default(), Vec::new(), Vec::new());
+    snake.render(&mut f, &arena, &mut top, &mut bottom, &CannibalStyle::default(), 0).unwrap();
+
+    let (_, last) = f.rsplit_once(&format!("\x1b[38;5;{}m", snake.color)).unwrap();
+    assert!(last.starts_with("\x1b[3;5H█"));
+    assert!(!f.contains("\x1b[48;5;"));
+    assert!(top.is_empty() && bottom.is_empty());
+  }
+
+  #[test]
+  fn wrapping_snakes_straddle_both_edges() {
+    let cells = |straddle: bool| {
+      let mut snake = snake(Strategy::Eat, Point::new(1, 4), 55);
+      snake.body = [1, 0, 39, 38, 37].into_iter().map(|x| Point::new(x, 4)).collect();
+      snake.straddle = straddle;
+      let mut f = Frame::default();
+      snake.render(&mut f, &Arena::new(0, 0, 40, 20), &mut Vec::new(), &mut Vec::new(), &CannibalStyle::default(), 0).unwrap();
+      f.matches('▀').count()
+    };
+    assert_eq!(cells(true), 5);
+    assert_eq!(cells(false), 2);
+  }
+
+  #[test]
+  fn body_gradient_dims_toward_the_tail() {
+    let mut snake = snake(Strategy::Eat, Point::new(4, 4), 55);
+    snake.body = (4..10).map(|x| Point::new(x, 4)).collect();
+    snake.gradient = true;
+    let arena = Arena::new(0, 0, 40, 20);
+    let (mut f, mut top, mut bottom) = (Frame::default(), Vec::new(), Vec::new());
+    snake.render(&mut f, &arena, &mut top, &mut bottom, &CannibalStyle::default(), 0).unwrap();
+
+    let colors: Vec<u8> = f.split("\x1b[38;5;").skip(1).map(|c| c[..c.find('m').unwrap()].parse().unwrap()).collect();
+    assert_eq!(colors.len(), 6);
+    assert_eq!(colors[0], snake.color);
+    let brightness: Vec<f32> = colors.iter().map(|&c| crate::esc::luminance(c)).collect();
+    assert!(brightness.windows(2).all(|w| w[0] >= w[1]));
+    assert!(brightness[0] > brightness[5]);
+  }
+
+  #[test]
+  fn cannibal_heads_stand_out_and_pulse() {
+    let render = |snake: &Snake, style: &CannibalStyle, frame: usize| {
+      let mut f = Frame::default();
+      snake.render(&mut f, &Arena::new(0, 0, 40, 20), &mut Vec::new(), &mut Vec::new(), style, frame).unwrap();
+      f.split("\x1b[38;5;").skip(1).map(|c| c[..c.find('m').unwrap()].parse().unwrap()).collect::<Vec<u8>>()
+    };
+    let mut snake = snake(Strategy::Eat, Point::new(4, 4), 55);
+    snake.body = (4..8).map(|x| Point::new(x, 4)).collect();
+    let style = CannibalStyle { body: Some(93), ..Default::default() };
+
+    let plain = render(&snake, &style, 0);
+    snake.cannibal = Instant::now();
+    let cannibal = render(&snake, &style, 0);
+    assert_ne!(cannibal[0], plain[0]);
+    assert_eq!(cannibal[0], style.head);
+    assert_eq!(render(&snake, &style, style.pulse_frames)[0], style.pulse);
+    assert!(plain[1..].iter().all(|&c| c == snake.color));
+    assert!(cannibal[1..].iter().all(|&c| c == 93));
+  }
+
+  #[test]
+  fn food_placement_ends_on_a_full_board() {
+    let end = Point::new(2, 1);
+    let mut snake = snake(Strategy::Eat, Point::new(0, 0), 55);
+    snake.body = VecDeque::from([Point::new(0, 0), Point::new(0, 1), Point::new(1, 1), Point::new(1, 0)]);
+    let mut rng = Rng::seeded(3);
+    let food = Food::random_free(Effect::None, &mut rng, &end, std::slice::from_ref(&snake));
+    assert!(food.position.x < 2 && food.position.y < 2);
+
+    snake.body.pop_back();
+    let food = Food::random_free(Effect::None, &mut rng, &end, std::slice::from_ref(&snake));
+    assert_eq!(food.position, Point::new(1, 0));
+  }
+
+  #[test]
+  fn segment_glyphs_follow_turns() {
+    use Direction::*;
+    assert_eq!(segment_glyph(None, Some(Right), true), '▀');
+    assert_eq!(segment_glyph(Some(Up), None, false), '▄');
+    assert_eq!(segment_glyph(Some(Left), Some(Right), true), '▀');
+    assert_eq!(segment_glyph(Some(Up), Some(Down), false), '▄');
+
+    for (prev, next, top, bottom) in [(Left, Up, '▘', '▖'), (Up, Right, '▝', '▗'), (Right, Down, '▝', '▗'), (Down, Left, '▘', '▖')] {
+      assert_eq!(segment_glyph(Some(prev), Some(next), true), top);
+      assert_eq!(segment_glyph(Some(next), Some(prev), true), top);
+      assert_eq!(segment_glyph(Some(prev), Some(next), false), bottom);
+    }
+  }
+
+  #[test]
+  fn tick_based_speed_moves_every_few_ticks() {
+    let mut snakes = [snake(Strategy::Eat, Point::new(4, 4), 40), snake(Strategy::Eat, Point::new(8, 8), 80)];
+    let tick = Duration::from_millis(10);
+    let moves: Vec<Vec<usize>> = snakes
+      .iter_mut()
+      .map(|snake| (1..=40).filter(|_| snake.can_move_ticks(tick)).collect())
+      .collect();
+    assert_eq!(moves[0], [4, 8, 12, 16, 20, 24, 28, 32, 36, 40]);
+    assert_eq!(moves[1], [8, 16, 24, 32, 40]);
+  }
+
+  #[test]
+  fn splitting_cuts_off_the_back_half() {
+    let mut snakes = vec![snake(Strategy::Player, Point::new(10, 10), 55)];
+    snakes[0].body = (0..9).map(|i| Point::new(10 - i, 10)).collect();
+    snakes[0].dir = Direction::Right;
+    let (mut rng, mut arena) = (Rng::seeded(1), Arena::new(0, 0, 40, 20));
+
+    assert_eq!(Snake::split(&mut snakes, 0, Strategy::Eat, &mut rng, &mut arena), Some(1));
+    let (parent, child) = (&snakes[0], &snakes[1]);
+    assert_eq!(parent.len() + child.len(), 9);
+    assert_eq!((parent.len(), child.len()), (5, 4));
+    for snake in &snakes {
+      assert!(snake.body.iter().zip(snake.body.iter().skip(1)).all(|(a, b)| Direction::between(a, b).is_some()));
+    }
+    assert_eq!(*child.head(), Point::new(2, 10));
+    assert_eq!(child.direction(), Direction::Left);
+    assert_eq!(child.strategy(), Strategy::Eat);
+    assert_ne!(child.team, parent.team);
+    assert_ne!(child.color, parent.color);
+
+    assert_eq!(Snake::split(&mut snakes, 1, Strategy::Eat, &mut rng, &mut arena), None);
+    assert_eq!(snakes.len(), 2);
+  }
+
+  #[test]
+  fn split_snakes_take_an_unused_team() {
+    let mut snakes: Vec<Snake> = (0..3).map(|i| snake(Strategy::Eat, Point::new(10 + i * 5, 10), 55)).collect();
+    for (snake, team) in snakes.iter_mut().zip([u8::MAX, 0, u8::MAX - 2]) {
+      snake.team = team;
+    }
+    snakes[0].body = std::iter::once(Point::new(10, 10)).chain(line(Point::new(10, 10), 8)).collect();
+    let (mut rng, mut arena) = (Rng::seeded(1), Arena::new(0, 0, 40, 20));
+
+    assert_eq!(Snake::split(&mut snakes, 0, Strategy::Eat, &mut rng, &mut arena), Some(3));
+    assert_eq!(snakes[3].team, 1);
+  }
+
+  #[test]
+  fn death_shrink_sets_how_fast_dead_snakes_shed_their_body() {
+    let moves_to_respawn = |shrink, pause| {
+      let mut snakes = [snake(Strategy::Eat, Point::new(10, 10), 55)];
+      snakes[0].body = (0..50).map(|i| Point::new(10, 10 + i)).collect();
+      snakes[0].dir = Direction::Up;
+      snakes[0].death_shrink = shrink;
+      let (mut rng, mut arena) = (Rng::seeded(1), Arena::new(0, 0, 40, 40));
+      arena.rotten.push(Point::new(10, 9));
+      Snake::serpentine(&mut snakes, 0, &mut rng, &mut arena);
+      assert!(!snakes[0].is_alive());
+      arena.rotten.clear();
+      (1..100)
+        .find(|_| {
+          std::thread::sleep(pause);
+          Snake::serpentine(&mut snakes, 0, &mut rng, &mut arena);
+          snakes[0].is_alive()
+        })
+        .unwrap()
+    };
+
+    // The move that kills a snake already sheds the first segments
+    assert_eq!(moves_to_respawn(DeathShrink::PerMove(1), Duration::ZERO), 47);
+    assert_eq!(moves_to_respawn(DeathShrink::PerMove(10), Duration::ZERO), 5);
+    assert_eq!(moves_to_respawn(DeathShrink::Within(Duration::from_millis(30)), Duration::from_millis(20)), 3);
+  }
+
+  #[test]
+  fn stacking_rules_decide_how_repeated_effects_combine() {
+    let eat_twice = |effect, rule| {
+      let mut snake = snake(Strategy::Eat, Point::new(4, 4), 55);
+      snake.stacking.set(effect, rule);
+      let food = Food::new(effect, Point::new(4, 4));
+      food.apply_effect(&mut snake);
+      let first = snake.effect_left(effect).unwrap();
+      std::thread::sleep(Duration::from_millis(20));
+      food.apply_effect(&mut snake);
+      (first, snake.effect_left(effect).unwrap(), snake)
+    };
+    let dose = Duration::from_secs(EFFECT_SECONDS);
+
+    let (first, second, extended) = eat_twice(Effect::Cannibal, StackRule::Extend);
+    assert!(second > first && second > dose + dose / 2);
+    assert!(extended.cannibal.elapsed() >= Duration::from_millis(20));
+    let (first, second, _) = eat_twice(Effect::Magnet, StackRule::Refresh);
+    assert!(second > first - Duration::from_millis(20) && second <= dose);
+    let (first, second, _) = eat_twice(Effect::Cannibal, StackRule::Replace);
+    assert!(second < first && first - second >= Duration::from_millis(20));
+
+    assert_eq!(eat_twice(Effect::Speed, StackRule::Extend).2.boost(), 6);
+    assert_eq!(eat_twice(Effect::Speed, StackRule::Refresh).2.boost(), 3);
+    assert_eq!(eat_twice(Effect::Speed, StackRule::Replace).2.boost(), 3);
+
+    let mut snake = snake(Strategy::Eat, Point::new(4, 4), 55);
+    snake.stacking.set(Effect::Magnet, StackRule::Replace);
+    Food::new(Effect::Cannibal, Point::new(4, 4)).apply_effect(&mut snake);
+    Food::new(Effect::Speed, Point::new(4, 4)).apply_effect(&mut snake);
+    Food::new(Effect::Magnet, Point::new(4, 4)).apply_effect(&mut snake);
+    assert!(snake.is_magnetized() && !snake.is_cannibal());
+    assert_eq!(snake.boost(), 0);
+
+    for _ in 0..5 {
+      Food::new(Effect::Cannibal, Point::new(4, 4)).apply_effect(&mut snake);
+    }
+    snake.stacking.set(Effect::Cannibal, StackRule::Extend);
+    for _ in 0..5 {
+      Food::new(Effect::Cannibal, Point::new(4, 4)).apply_effect(&mut snake);
+    }
+    assert!(snake.effect_left(Effect::Cannibal).unwrap() <= dose * MAX_STACK);
+  }
+
+  #[test]
+  fn self_crash_counts_a_death_without_a_killer() {
+    let (head, other) = (Point::new(10, 10), Point::new(20, 10));
+    let mut body = line(head, 3);
+    body.push(Point::new(11, 13));
+    body.extend(line(Point::new(11, 9), 4));
+    let mut snakes = Scenario::default().snake(Strategy::Eat, head, &body).snake(Strategy::Eat, other, &line(other, 3)).snakes;
+    snakes[0].dir = Direction::Right;
+    let mut arena = Arena::new(0, 0, 40, 20);
+    Snake::serpentine(&mut snakes, 0, &mut Rng::seeded(1), &mut arena);
+
+    assert!(!snakes[0].alive);
+    assert_eq!((snakes[0].stats().deaths, snakes[0].stats().kills), (1, 0));
+    assert_eq!(snakes[1].stats().kills, 0);
+
+    snakes[0].kill_policy.self_deaths = false;
+    Snake::resolve_death(&mut snakes, 0, Some(0));
+    assert_eq!(snakes[0].stats().deaths, 1);
+  }
+
+  #[test]
+  fn teammates_are_only_credited_when_the_policy_allows() {
+    let (a, b) = (Point::new(10, 10), Point::new(20, 10));
+    let mut snakes = Scenario::default().snake(Strategy::Eat, a, &line(a, 3)).snake(Strategy::Eat, b, &line(b, 3)).snakes;
+    snakes[1].team = snakes[0].team;
+    Snake::resolve_death(&mut snakes, 0, Some(1));
+    assert_eq!((snakes[0].stats().deaths, snakes[1].stats().kills, snakes[1].len()), (1, 0, 4));
+
+    snakes[0].kill_policy.team_kills = true;
+    Snake::resolve_death(&mut snakes, 0, Some(1));
+    assert_eq!((snakes[0].stats().deaths, snakes[1].stats().kills, snakes[1].len()), (2, 1, 8));
+  }
+
+  #[test]
+  fn cannibal_food_needs_a_minimum_length() {
+    let eat = |len: usize| {
+      let mut snake = snake(Strategy::Eat, Point::new(4, 4), 55);
+      snake.body.truncate(len);
+      snake.cannibal_min_len = 6;
+      Food::new(Effect::Cannibal, Point::new(4, 4)).apply_effect(&mut snake);
+      snake
+    };
+
+    let short = eat(3);
+    assert!(!short.is_cannibal());
+    assert_eq!(short.len(), 4);
+    let long = eat(6);
+    assert!(long.is_cannibal());
+    assert_eq!(long.len(), 7);
+  }
+
+  #[test]
+  fn speed_food_boost_is_capped() {
+    let mut snake = snake(Strategy::Eat, Point::new(4, 4), 55);
+    snake.max_boost = 9;
+    for _ in 0..5 {
+      Food::new(Effect::Speed, Point::new(4, 4)).apply_effect(&mut snake);
+    }
+    assert_eq!(snake.boost(), 9);
+    assert_eq!(snake.move_interval(), Duration::from_millis(46));
+
+    let mut meter = String::new();
+    snake.boost_meter(&mut meter, 6).unwrap();
+    assert_eq!(meter, "[██████]");
+
+    snake.max_boost = 18;
+    meter.clear();
+    snake.boost_meter(&mut meter, 6).unwrap();
+    assert_eq!(meter, "[███░░░]");
+
+    // Lowering the cap below a boost already stacked keeps the meter full
+    snake.max_boost = 3;
+    meter.clear();
+    snake.boost_meter(&mut meter, 6).unwrap();
+    assert_eq!(meter, "[██████]");
+  }
+
+  #[test]
+  fn speed_food_stacks_without_a_cap_by_default() {
+    let mut snake = snake(Strategy::Eat, Point::new(4, 4), 55);
+    for _ in 0..5 {
+      Food::new(Effect::Speed, Point::new(4, 4)).apply_effect(&mut snake);
+    }
+    assert_eq!(snake.move_interval(), Duration::from_millis(40));
+  }
+
+  #[test]
+  fn teammates_pass_through_each_other() {
+    let mut snakes = [snake(Strategy::Eat, Point::new(10, 10), 55), snake(Strategy::Kill, Point::new(11, 10), 55)];
+    snakes[1].team = 1;
+    let (arena, mut killer) = (Arena::new(0, 0, 40, 20), None);
+    assert!(Snake::is_crash(&snakes, 0, &Point::new(11, 10), &arena, &mut killer));
+    assert_eq!(killer, Some(1));
+
+    snakes[1].team = 0;
+    let mut killer = None;
+    assert!(!Snake::is_crash(&snakes, 0, &Point::new(11, 10), &arena, &mut killer));
+    assert_eq!(killer, None);
+    assert!(Snake::is_crash(&snakes, 0, &Point::new(10, 10), &arena, &mut killer));
+  }
+
+  #[test]
+  fn occupancy_grid_agrees_with_scanning_every_body() {
+    let (a, b, c, d) = (Point::new(10, 10), Point::new(14, 10), Point::new(12, 6), Point::new(30, 30));
+    let mut bent = line(b, 4);
+    bent.extend([Point::new(13, 14), Point::new(12, 14)]);
+    let mut snakes = Scenario::default()
+      .snake(Strategy::Eat, a, &line(a, 5))
+      .cannibal()
+      .snake(Strategy::Kill, b, &bent)
+      .snake(Strategy::Eat, c, &line(c, 6))
+      .snake(Strategy::Eat, d, &[Point::new(31, 30), Point::new(31, 31), Point::new(30, 31), d])
+      .snakes;
+    snakes[2].team = snakes[0].team;
+    snakes[3].tron = true;
+
+    let plain = Arena::new(0, 0, 40, 20);
+    let mut gridded = Arena::new(0, 0, 40, 20);
+    let mut occupancy = Occupancy::default();
+    occupancy.refresh(&snakes, Point::new(plain.size.x, plain.height()));
+    gridded.occupancy = Some(occupancy);
+
+    for self_collision in [SelfCollision::Lethal, SelfCollision::Ignore] {
+      snakes.iter_mut().for_each(|snake| snake.self_collision = self_collision);
+      for idx in 0..snakes.len() {
+        for head in (0..plain.size.x).flat_map(|x| (0..plain.height()).map(move |y| Point::new(x, y))) {
+          let (mut scanned, mut looked_up) = (None, None);
+          assert_eq!(
+            Snake::is_crash(&snakes, idx, &head, &plain, &mut scanned),
+            Snake::is_crash(&snakes, idx, &head, &gridded, &mut looked_up),
+            "snake {idx} moving to {head:?}"
+          );
+          assert_eq!(scanned, looked_up);
+        }
+      }
+    }
+
+    let tail = *snakes[1].tail();
+    assert!(!Snake::is_crash(&snakes, 0, &tail, &gridded, &mut None));
+
+    // Growing stacks segments on the tail, which a cannibal can't bite through
+    snakes[1].grow(1);
+    gridded.sync(&snakes, 1);
+    assert!(Snake::is_crash(&snakes, 0, &tail, &gridded, &mut None));
+    assert!(Snake::is_crash(&snakes, 0, &tail, &plain, &mut None));
+  }
+
+  #[test]
+  fn rotten_cells_are_lethal() {
+    let mut snakes = [snake(Strategy::Eat, Point::new(10, 10), 55)];
+    let mut arena = Arena::new(0, 0, 40, 20);
+    arena.rotten.push(Point::new(11, 10));
+    assert!(Snake::is_crash(&snakes, 0, &Point::new(11, 10), &arena, &mut None));
+    assert!(!Snake::is_crash(&snakes, 0, &Point::new(10, 11), &arena, &mut None));
+
+    snakes[0].dir = Direction::Right;
+    Snake::serpentine(&mut snakes, 0, &mut Rng::seeded(1), &mut arena);
+    assert!(!snakes[0].is_alive());
+  }
+
+  #[test]
+  fn pack_snakes_split_the_intercepts() {
+    let (player, a, b) = (Point::new(20, 20), Point::new(30, 20), Point::new(30, 21));
+    let mut scenario = Scenario::default()
+      .snake(Strategy::Player, player, &[Point::new(19, 20), Point::new(18, 20)])
+      .snake(Strategy::Pack, a, &line(a, 3))
+      .snake(Strategy::Pack, b, &line(b, 3));
+    scenario.snakes[0].dir = Direction::Right;
+
+    // Both would go for the same cell on their own
+    let snakes = &scenario.snakes;
+    assert_eq!(snakes[1].find_target(snakes, &scenario.food), snakes[2].find_target(snakes, &scenario.food));
+
+    let mut plan = PackPlan::default();
+    let first = Snake::coordinated_target(snakes, 1, &scenario.food, &mut plan);
+    let second = Snake::coordinated_target(snakes, 2, &scenario.food, &mut plan);
+    assert_ne!(first, second);
+    assert!(snakes[0].intercepts().contains(&first) && snakes[0].intercepts().contains(&second));
+    // Deciding again keeps the claim of the other one
+    assert_eq!(Snake::coordinated_target(snakes, 1, &scenario.food, &mut plan), first);
+
+    // Once the player turns the intercepts move with it
+    scenario.snakes[0].dir = Direction::Down;
+    let snakes = &scenario.snakes;
+    let turned = Snake::coordinated_target(snakes, 1, &scenario.food, &mut plan);
+    assert!(snakes[0].intercepts().contains(&turned) && turned != first);
+  }
+
+  #[test]
+  fn contested_food_is_shared_or_moves_away() {
+    let (cell, a, b) = (Point::new(10, 10), Point::new(10, 11), Point::new(11, 10));
+    let eat = |contest: FoodContest, effect: Effect| {
+      let mut scenario = Scenario::default().snake(Strategy::Eat, cell, &[a]).snake(Strategy::Eat, cell, &[b]).food(effect, cell);
+      let mut arena = Arena::new(0, 0, 40, 20);
+      let mut rng = Rng::seeded(1);
+      let first = Snake::eat(&mut scenario.snakes, 0, &mut rng, &mut scenario.food, &mut arena, Duration::ZERO, contest).len();
+      let second = Snake::eat(&mut scenario.snakes, 1, &mut rng, &mut scenario.food, &mut arena, Duration::ZERO, contest).len();
+      ((first, second), (scenario.snakes[0].len(), scenario.snakes[1].len()), scenario.food[0].position)
+    };
+
+    let (eaten, lengths, food) = eat(FoodContest::Contested, Effect::Nourish);
+    assert_eq!((eaten, lengths), ((0, 0), (2, 2)));
+    assert_ne!(food, cell);
+
+    assert_eq!(eat(FoodContest::FirstCome, Effect::Nourish).0, (1, 0));
+    assert_eq!(eat(FoodContest::FirstCome, Effect::Nourish).1, (4, 2));
+    // Nourishing food grows by two so each gets one, plain food rounds its single segment up for both
+    let (eaten, lengths, _) = eat(FoodContest::Split, Effect::Nourish);
+    assert_eq!((eaten, lengths), ((1, 0), (3, 3)));
+    assert_eq!(eat(FoodContest::Split, Effect::None).1, (3, 3));
+  }
+
+  #[test]
+  fn eating_keeps_the_occupancy_grid_in_sync() {
+    let (cell, a, b) = (Point::new(10, 10), Point::new(10, 11), Point::new(11, 10));
+    let mut scenario = Scenario::default().snake(Strategy::Eat, cell, &[a]).snake(Strategy::Eat, cell, &[b]).food(Effect::Nourish, cell);
+    let mut arena = Arena::new(0, 0, 40, 20);
+    let size = Point::new(arena.size.x, arena.height());
+    let mut occupancy = Occupancy::default();
+    occupancy.refresh(&scenario.snakes, size);
+    arena.occupancy = Some(occupancy);
+
+    let eaten = Snake::eat(&mut scenario.snakes, 0, &mut Rng::seeded(1), &mut scenario.food, &mut arena, Duration::ZERO, FoodContest::Split);
+    assert_eq!(eaten.len(), 1);
+    let mut rebuilt = Occupancy::default();
+    rebuilt.refresh(&scenario.snakes, size);
+    let synced = arena.occupancy.as_ref().unwrap();
+    for p in [cell, a, b] {
+      assert_eq!(synced.occupants(&p), rebuilt.occupants(&p), "{p:?}");
+    }
+  }
+
+  #[test]
+  fn capped_snakes_turn_nourishing_food_into_score() {
+    let head = Point::new(10, 10);
+    let eat = |max_len: Option<usize>| {
+      let mut scenario = Scenario::default().snake(Strategy::Eat, head, &line(head, 7)).food(Effect::Nourish, head);
+      scenario.snakes[0].max_len = max_len;
+      let mut arena = Arena::new(0, 0, 40, 20);
+      Snake::eat(&mut scenario.snakes, 0, &mut Rng::seeded(1), &mut scenario.food, &mut arena, Duration::ZERO, FoodContest::FirstCome);
+      (scenario.snakes[0].len(), scenario.snakes[0].score())
+    };
+
+    let (free, capped) = (eat(None), eat(Some(9)));
+    assert_eq!((free.0, capped.0), (10, 9));
+    assert_eq!(capped.1, free.1);
+    assert_eq!(eat(Some(8)).0, 8);
+  }
+
+  #[test]
+  fn stacked_food_is_eaten_together() {
+    let head = Point::new(10, 10);
+    let mut scenario = Scenario::default().snake(Strategy::Eat, head, &line(head, 3)).food(Effect::None, head).food(Effect::Nourish, head);
+    let mut arena = Arena::new(0, 0, 40, 20);
+    let eaten = Snake::eat(&mut scenario.snakes, 0, &mut Rng::seeded(1), &mut scenario.food, &mut arena, Duration::ZERO, FoodContest::FirstCome);
+    assert!(eaten.len() == 2 && eaten[0].effect() == Effect::None && eaten[1].effect() == Effect::Nourish);
+    assert_eq!(scenario.snakes[0].len(), 4 + 1 + 2);
+    assert!(scenario.food.iter().all(|food| food.position != head));
+  }
+
+  #[test]
+  fn simultaneous_head_on_collisions_have_no_winner() {
+    let (a, b) = (Point::new(10, 10), Point::new(12, 10));
+    let clash = |simultaneous: bool| {
+      let body = |head: Point, step: i32| (1..6).map(|i| head + (i * step, 0)).collect::<Vec<_>>();
+      let mut scenario = Scenario::default().snake(Strategy::Eat, a, &body(a, -1)).snake(Strategy::Eat, b, &body(b, 1));
+      for (snake, dir) in scenario.snakes.iter_mut().zip([Direction::Right, Direction::Left]) {
+        (snake.dir, snake.turns) = (dir, VecDeque::new());
+      }
+      let (mut arena, mut rng) = (Arena::new(0, 0, 40, 20), Rng::seeded(1));
+      if simultaneous {
+        Snake::serpentine_all(&mut scenario.snakes, &[0, 1], &mut rng, &mut arena);
+      } else {
+        Snake::serpentine(&mut scenario.snakes, 0, &mut rng, &mut arena);
+        Snake::serpentine(&mut scenario.snakes, 1, &mut rng, &mut arena);
+      }
+      (scenario.snakes[0].is_alive(), scenario.snakes[1].is_alive())
+    };
+
+    assert_eq!(clash(false), (true, false));
+    assert_eq!(clash(true), (false, false));
+  }
+
+  #[test]
+  fn ramped_speed_survives_death() {
+    let head = Point::new(0, 10);
+    let mut scenario = Scenario::default().snake(Strategy::Eat, head, &line(head, 4));
+    let snake = &mut scenario.snakes[0];
+    (snake.dir, snake.turns) = (Direction::Left, VecDeque::new());
+    snake.ramp_up(10);
+    let mut arena = Arena::new(0, 0, 40, 20);
+    arena.wrap_x = false;
+    Snake::serpentine(&mut scenario.snakes, 0, &mut Rng::seeded(1), &mut arena);
+    assert!(!scenario.snakes[0].is_alive());
+    assert_eq!(scenario.snakes[0].speed, RESPAWN_SPEED - 10);
+  }
+
+  #[test]
+  fn reversing_heads_away_from_the_old_tail() {
+    let head = Point::new(10, 10);
+    let mut snake = snake(Strategy::Eat, head, 55);
+    snake.body = VecDeque::from([head, Point::new(9, 10), Point::new(9, 11), Point::new(9, 12)]);
+    snake.dir = Direction::Right;
+    snake.reverse();
+    assert_eq!((*snake.head(), snake.dir), (Point::new(9, 12), Direction::Down));
+
+    snake.body = VecDeque::from(vec![head; 4]);
+    snake.reverse();
+    assert_eq!(snake.dir, Direction::Up);
+  }
+
+  #[test]
+  fn growth_stacks_on_the_tail() {
+    let head = Point::new(10, 10);
+    let mut snake = snake(Strategy::Eat, head, 55);
+    snake.body = std::iter::once(head).chain(line(head, 2)).collect();
+    snake.grow(2);
+    assert_eq!(Vec::from(snake.body), [head, Point::new(10, 11), Point::new(10, 12), Point::new(10, 12), Point::new(10, 12)]);
+  }
+
+  #[test]
+  fn bites_within_the_cooldown_only_apply_once() {
+    let head = Point::new(10, 10);
+    let bites = |cooldown: Duration| {
+      let mut scenario = Scenario::default()
+        .snake(Strategy::Cannibal, head, &line(head, 3))
+        .cannibal()
+        .snake(Strategy::Eat, Point::new(13, 10), &[Point::new(12, 10), Point::new(11, 10), head])
+        .snake(Strategy::Eat, Point::new(10, 7), &[Point::new(10, 8), Point::new(10, 9), head]);
+      let mut arena = Arena::new(0, 0, 40, 20);
+      for _ in 0..2 {
+        Snake::eat(&mut scenario.snakes, 0, &mut Rng::seeded(1), &mut scenario.food, &mut arena, cooldown, FoodContest::FirstCome);
+      }
+      scenario.snakes.iter().map(|snake| snake.len()).collect::<Vec<_>>()
+    };
+
+    assert_eq!(bites(Duration::from_secs(60)), [5, 3, 4]);
+    assert_eq!(bites(Duration::ZERO), [6, 3, 3]);
+  }
+
+  #[test]
+  fn swap_food_trades_heads_with_nearest_rival() {
+    // The teammate is closer but only rivals get swapped with
+    let (a, b, c, mate) = (Point::new(10, 10), Point::new(14, 10), Point::new(30, 30), Point::new(8, 10));
+    let mut scenario = Scenario::default()
+      .snake(Strategy::Eat, a, &line(a, 3))
+      .snake(Strategy::Eat, b, &line(b, 5))
+      .snake(Strategy::Eat, c, &line(c, 2))
+      .snake(Strategy::Eat, mate, &line(mate, 2))
+      .food(Effect::Swap, a);
+    (scenario.snakes[0].dir, scenario.snakes[1].dir, scenario.snakes[3].team) = (Direction::Up, Direction::Left, 0);
+    let (eater, rival) = (scenario.snakes[0].body.clone(), scenario.snakes[1].body.clone());
+
+    let mut arena = Arena::new(0, 0, 40, 40);
+    let eaten = Snake::eat(&mut scenario.snakes, 0, &mut Rng::seeded(1), &mut scenario.food, &mut arena, Duration::ZERO, FoodContest::FirstCome);
+    assert_eq!(eaten.len(), 1);
+    let snakes = &scenario.snakes;
+    assert_eq!((*snakes[0].head(), *snakes[1].head()), (b, a));
+    assert!(snakes.iter().all(|snake| snake.is_alive()));
+    // Everything behind the heads stays put, the eater only grew at its tail
+    assert!(snakes[0].body.iter().skip(1).zip(eater.iter().skip(1)).all(|(p, q)| p == q));
+    assert_eq!(snakes[0].len(), eater.len() + 1);
+    assert!(snakes[1].body.iter().skip(1).eq(rival.iter().skip(1)));
+    for snake in &snakes[..2] {
+      assert!(snake.body.iter().skip(1).zip(snake.body.iter().skip(2)).all(|(p, q)| p == q || p.quick_distance(q) == 1));
+    }
+    assert_eq!((snakes[0].dir, snakes[1].dir), (Direction::Left, Direction::Up));
+    assert_eq!((*snakes[2].head(), *snakes[3].head()), (c, mate));
+
+    // A rival whose whole body is still stacked on its head leaves the eater nowhere safe to land
+    let mut scenario = Scenario::default().snake(Strategy::Eat, a, &line(a, 5)).snake(Strategy::Eat, b, &[b, b]).food(Effect::Swap, a);
+    Snake::eat(&mut scenario.snakes, 0, &mut Rng::seeded(1), &mut scenario.food, &mut arena, Duration::ZERO, FoodContest::FirstCome);
+    assert!(!scenario.snakes[0].is_alive() && scenario.snakes[1].is_alive());
+    assert_eq!(scenario.snakes[1].totals.kills, 1);
+  }
+
+  #[test]
+  fn memory_breaks_loops_around_obstacles() {
+    // A rival lies flat between the hunter and its target, greedy seeking alone keeps wiggling along it
+    let steps_to_target = |forget: bool| {
+      let wall: Vec<Point> = (10..31).map(|x| Point::new(x, 20)).collect();
+      let mut snakes = [snake(Strategy::Eat, Point::new(20, 15), 55), snake(Strategy::Eat, wall[0], 55)];
+      snakes[0].body = (0..4).map(|i| Point::new(20, 15 - i)).collect();
+      snakes[0].dir = Direction::Down;
+      snakes[1].body = wall.into_iter().collect();
+      snakes[1].team = 1;
+      let (mut arena, mut rng, target) = (Arena::new(0, 0, 40, 20), Rng::seeded(1), Point::new(20, 25));
+
+      (0..300).position(|_| {
+        if forget {
+          snakes[0].recent.clear();
+        }
+        Snake::seek(&mut snakes, 0, &target, &arena);
+        Snake::serpentine(&mut snakes, 0, &mut rng, &mut arena);
+        *snakes[0].head() == target
+      })
+    };
+
+    assert_eq!(steps_to_target(true), None);
+    assert!(steps_to_target(false).is_some());
+  }
+
+  #[test]
+  fn survival_time_ranks_equal_lengths() {
+    let weights = ScoreWeights { survival: 2, ..Default::default() };
+    let mut veteran = snake(Strategy::Eat, Point::new(2, 2), 55);
+    let mut rookie = snake(Strategy::Eat, Point::new(9, 9), 55);
+    veteran.score_weights = weights;
+    rookie.score_weights = weights;
+    veteran.alive_since -= Duration::from_secs(30);
+    rookie.alive_since -= Duration::from_secs(5);
+
+    assert_eq!(veteran.len(), rookie.len());
+    assert_eq!(veteran.score(), 8 + 60);
+    assert_eq!(rookie.score(), 8 + 10);
+  }
+
+  #[test]
+  fn score_strategy_prefers_kills_when_they_pay() {
+    let (head, prey) = (Point::new(10, 10), Point::new(20, 10));
+    let scenario = |kills| {
+      let mut scenario = Scenario::default()
+        .snake(Strategy::Score, head, &line(head, 3))
+        .speed(40)
+        .snake(Strategy::Eat, prey, &line(prey, 3))
+        .speed(60)
+        .food(Effect::Nourish, Point::new(14, 10));
+      scenario.snakes[0].score_weights.kills = kills;
+      scenario
+    };
+
+    assert_eq!(scenario(0).target(), Point::new(14, 10));
+    assert_eq!(scenario(20).target(), prey);
+  }
+
+  #[test]
+  fn magnet_pulls_the_nearest_food_until_it_is_next_to_the_head() {
+    let head = Point::new(10, 10);
+    let mut scenario = Scenario::default()
+      .snake(Strategy::Eat, head, &line(head, 3))
+      .magnetized()
+      .food(Effect::Magnet, Point::new(12, 10))
+      .food(Effect::None, Point::new(20, 10))
+      .food(Effect::Speed, Point::new(10, 40));
+    let mut seen = Vec::new();
+    for _ in 0..12 {
+      Snake::pull_food(&scenario.snakes, 0, &mut scenario.food);
+      seen.push(scenario.food[1].position.x);
+    }
+    assert_eq!(seen, [19, 18, 17, 16, 15, 14, 13, 12, 11, 11, 11, 11]);
+    assert_eq!(scenario.food[0].position, Point::new(12, 10));
+    assert_eq!(scenario.food[2].position, Point::new(10, 40));
+  }
+
+  #[test]
+  fn nearest_magnet_wins_contested_food() {
+    let (a, b) = (Point::new(10, 10), Point::new(30, 10));
+    let mut scenario = Scenario::default()
+      .snake(Strategy::Eat, a, &line(a, 3))
+      .magnetized()
+      .snake(Strategy::Eat, b, &line(b, 3))
+      .magnetized()
+      .food(Effect::None, Point::new(24, 10));
+    Snake::pull_food(&scenario.snakes, 0, &mut scenario.food);
+    assert_eq!(scenario.food[0].position, Point::new(24, 10));
+    Snake::pull_food(&scenario.snakes, 1, &mut scenario.food);
+    assert_eq!(scenario.food[0].position, Point::new(25, 10));
+  }
+
 }
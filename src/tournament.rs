@@ -0,0 +1,118 @@
+use crate::{
+  game::{Game, GameResult, Idle},
+  map::Strategy,
+};
+use std::collections::BTreeMap;
+
+// Plays a series of seeded elimination games on an 80x30 terminal and adds up how each strategy did.
+// The player joins on autopilot and every snake moves by simulation ticks so a seed always plays out the
+// same way. Games still running after max_ticks count as draws.
+pub struct Tournament {
+  games: usize,
+  seed: u64,
+  max_ticks: usize,
+  autopilot: Strategy,
+  setup: Box<dyn Fn(&mut Game)>,
+}
+
+// Totals across every game for the snakes playing one strategy
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StrategyReport {
+  pub entries: usize,
+  pub wins: usize,
+  pub score: usize,
+  pub kills: usize,
+  pub deaths: usize,
+}
+
+impl StrategyReport {
+  pub fn win_rate(&self) -> f32 {
+    self.wins as f32 / self.entries.max(1) as f32
+  }
+
+  pub fn average_score(&self) -> f32 {
+    self.score as f32 / self.entries.max(1) as f32
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TournamentReport {
+  pub games: usize,
+  pub draws: usize,
+  pub strategies: BTreeMap<&'static str, StrategyReport>,
+}
+
+impl Tournament {
+  pub fn new(games: usize) -> Self {
+    Self {
+      games,
+      seed: 0,
+      max_ticks: 10_000,
+      autopilot: Strategy::Eat,
+      setup: Box::new(|_| ()),
+    }
+  }
+
+  // Game n is seeded with seed + n
+  pub fn seed(&mut self, seed: u64) -> &mut Self {
+    self.seed = seed;
+    self
+  }
+
+  pub fn max_ticks(&mut self, ticks: usize) -> &mut Self {
+    self.max_ticks = ticks;
+    self
+  }
+
+  // How the player's snake plays, it's reported under the player strategy either way
+  pub fn autopilot(&mut self, strat: Strategy) -> &mut Self {
+    self.autopilot = strat;
+    self
+  }
+
+  // Configures every game on top of the tournament defaults, before the snakes spawn
+  pub fn setup(&mut self, setup: impl Fn(&mut Game) + 'static) -> &mut Self {
+    self.setup = Box::new(setup);
+    self
+  }
+
+  pub fn run(&self) -> GameResult<TournamentReport> {
+    let mut report = TournamentReport {
+      games: self.games,
+      ..Default::default()
+    };
+    for n in 0..self.games {
+      let mut game = Game::headless(80, 30);
+      game
+        .seed(self.seed.wrapping_add(n as u64))
+        .tron(true)
+        .tick_based_speed(true)
+        .idle_behavior(Idle::Autopilot(self.autopilot));
+      (self.setup)(&mut game);
+      game.reset();
+      // Games start paused
+      game.tick(Some(b'p'))?;
+      for _ in 0..self.max_ticks {
+        if game.summary().finished {
+          break;
+        }
+        game.tick(None)?;
+      }
+
+      let winner = game.summary().winner;
+      if winner.is_none() {
+        report.draws += 1;
+      }
+      for (i, snake) in game.snakes().iter().enumerate() {
+        let stats = snake.stats();
+        let entry = report.strategies.entry(snake.strategy().name()).or_default();
+        entry.entries += 1;
+        entry.wins += usize::from(winner == Some(i));
+        entry.score += snake.score();
+        entry.kills += stats.kills;
+        entry.deaths += stats.deaths;
+      }
+    }
+    Ok(report)
+  }
+}
@@ -0,0 +1,1722 @@
+use snakers::{CharSet, ConfigError, Direction, Effect, FoodEffect, Format, Frame, Game, GameError, GameView, Idle, Legend, Placement, Point, Preferences, Rect, Renderer, RestoreOnPanic, SelfCollision, Snake, Strategy, Teams, Tournament, WallPolicy, PARTICLE_FRAMES};
+use std::{
+  cell::{Cell, RefCell},
+  collections::{HashMap, HashSet},
+  fmt::{self, Write},
+  io,
+  panic::{self, AssertUnwindSafe},
+  rc::Rc,
+  sync::{Arc, Mutex},
+  time::{Duration, Instant},
+};
+
+fn solo() -> Game {
+  let mut game = Game::headless(80, 30);
+  game.seed(7).opponents(&[]).step_mode(true);
+  game
+}
+
+fn cursor_moves(frame: &str) -> Vec<(u16, u16)> {
+  frame
+    .split("\x1b[")
+    .filter_map(|seq| {
+      let (y, rest) = seq.split_once(';')?;
+      let (x, _) = rest.split_once('H')?;
+      Some((x.parse().ok()?, y.parse().ok()?))
+    })
+    .collect()
+}
+
+fn position_of(frame: &str, text: &str) -> Option<(u16, u16)> {
+  let end = frame.find(&format!("H{text}"))?;
+  let start = frame[..end].rfind("\x1b[")?;
+  cursor_moves(&frame[start..=end]).pop()
+}
+
+// Every cell a frame writes a character to, following absolute moves, forward moves and newlines
+fn drawn_cells(frame: &str) -> Vec<((u16, u16), char)> {
+  let (mut cells, mut cursor, mut chars) = (Vec::new(), (1, 1), frame.chars());
+  while let Some(c) = chars.next() {
+    match c {
+      '\x1b' => {
+        let seq: String = chars.by_ref().skip(1).take_while(|c| !c.is_ascii_alphabetic()).collect();
+        let end = frame[..frame.len() - chars.as_str().len()].chars().last().unwrap();
+        let args: Vec<u16> = seq.split(';').map(|n| n.parse().unwrap_or(1)).collect();
+        match end {
+          'H' => cursor = (args.get(1).copied().unwrap_or(1), args[0]),
+          'C' => cursor.0 += args[0],
+          _ => {}
+        }
+      }
+      '\n' => cursor = (1, cursor.1 + 1),
+      '\r' => cursor.0 = 1,
+      _ => {
+        cells.push((cursor, c));
+        cursor.0 += 1;
+      }
+    }
+  }
+  cells
+}
+
+#[test]
+fn spawn_renders_arena_and_scoreboard() {
+  let mut game = Game::headless(80, 30);
+  game.seed(7).opponents(&[Strategy::Eat, Strategy::Kill]);
+  let frame = game.tick(None).unwrap().to_string();
+
+  assert_eq!(game.snakes().len(), 3);
+  assert_eq!(game.food().len(), 12);
+  assert!(frame.contains('╔') && frame.contains('╝'));
+  for snake in game.snakes() {
+    assert!(frame.contains(&format!("{}: {}", snake.name, snake.score())));
+  }
+}
+
+#[test]
+fn player_moves_one_cell_per_step() {
+  let mut game = solo();
+  game.tick(Some(b'p')).unwrap();
+  let start = *game.snakes()[0].head();
+
+  let frame = game.tick(Some(b'w')).unwrap().to_string();
+  let head = *game.snakes()[0].head();
+
+  assert_ne!(head, start);
+  assert!((head.x == start.x) != (head.y == start.y));
+  assert!(frame.contains('▀') || frame.contains('▄'));
+}
+
+#[test]
+fn player_grows_after_eating() {
+  let mut game = solo();
+  game.tick(Some(b'p')).unwrap();
+  let target = game.food()[0].position;
+  let len = game.snakes()[0].len();
+
+  for _ in 0..2000 {
+    let head = *game.snakes()[0].head();
+    if head == target {
+      break;
+    }
+    game.tick(Some(if head.x != target.x { b'd' } else { b's' })).unwrap();
+  }
+  game.tick(Some(b's')).unwrap();
+
+  assert!(game.snakes()[0].len() > len);
+}
+
+#[test]
+fn each_axis_wraps_on_its_own() {
+  for (wrap_x, wrap_y) in [(false, false), (true, false), (false, true), (true, true)] {
+    for (key, dir, wraps) in [(b'd', Direction::Right, wrap_x), (b'w', Direction::Up, wrap_y)] {
+      let mut game = solo();
+      game.arena_size(12, 12).wrap_axes(wrap_x, wrap_y).spawn_protection(Duration::ZERO).player_start_dir(dir);
+      game.tick(Some(b'p')).unwrap();
+
+      let died = (0..30).any(|_| {
+        game.tick(Some(key)).unwrap();
+        !game.snakes()[0].is_alive()
+      });
+      assert_eq!(died, !wraps, "wrap {wrap_x}:{wrap_y} moving {dir:?}");
+    }
+  }
+}
+
+#[test]
+fn odd_height_arenas_have_no_extra_bottom_half() {
+  for wrap in [false, true] {
+    let mut game = solo();
+    game.arena_size(12, 9).wrap_axes(true, wrap).spawn_protection(Duration::ZERO).player_start_dir(Direction::Down);
+    game.tick(Some(b'p')).unwrap();
+
+    let mut died = false;
+    for _ in 0..40 {
+      game.tick(Some(b's')).unwrap();
+      let snake = &game.snakes()[0];
+      died |= !snake.is_alive();
+      assert!(died || snake.body.iter().all(|p| p.y < 18), "wrap {wrap} reached {:?}", snake.head());
+    }
+    assert_eq!(died, !wrap);
+  }
+}
+
+#[test]
+fn player_dies_against_walls() {
+  let mut game = solo();
+  game.wrap_axes(false, false);
+  game.tick(Some(b'p')).unwrap();
+  let len = game.snakes()[0].len();
+
+  let died = (0..300).any(|_| {
+    game.tick(Some(b'd')).unwrap();
+    !game.snakes()[0].is_alive()
+  });
+
+  assert!(died);
+  assert!(game.snakes()[0].len() < len);
+}
+
+#[test]
+fn scoreboard_placements_stay_on_screen() {
+  for placement in [Placement::Right, Placement::Left, Placement::Below, Placement::Hidden] {
+    let mut game = Game::headless(60, 24);
+    game.seed(3).scoreboard(placement);
+    game.tick(Some(b'p')).unwrap();
+    let frame = game.tick(None).unwrap().to_string();
+
+    for (x, y) in cursor_moves(&frame) {
+      assert!((1..=60).contains(&x) && (1..=24).contains(&y), "{placement:?} moved to {x}:{y}");
+    }
+    let names = game.snakes().iter().filter(|snake| frame.contains(snake.name)).count();
+    assert_eq!(names == 0, placement == Placement::Hidden);
+  }
+}
+
+fn deaths_while_steering_right(game: &mut Game, ticks: usize) -> usize {
+  let mut deaths = 0;
+  let mut alive = game.snakes()[0].is_alive();
+  for _ in 0..ticks {
+    game.tick(Some(b'd')).unwrap();
+    if alive && !game.snakes()[0].is_alive() {
+      deaths += 1;
+    }
+    alive = game.snakes()[0].is_alive();
+  }
+  deaths
+}
+
+#[test]
+fn respawned_player_is_protected() {
+  let mut game = solo();
+  game.wrap_axes(false, false).spawn_protection(Duration::from_secs(60));
+  game.tick(Some(b'p')).unwrap();
+  while game.snakes()[0].is_alive() {
+    game.tick(Some(b'd')).unwrap();
+  }
+  while !game.snakes()[0].is_alive() {
+    game.tick(Some(b'd')).unwrap();
+  }
+
+  assert!(game.snakes()[0].is_protected());
+  for &key in b"dsaw".repeat(10).iter() {
+    game.tick(Some(key)).unwrap();
+    assert!(game.snakes()[0].is_alive());
+  }
+  // Protection only covers crashes into snakes, walls still kill instead of letting it wrap
+  assert!(deaths_while_steering_right(&mut game, 300) > 0);
+}
+
+#[test]
+fn respawned_player_dies_without_protection() {
+  let mut game = solo();
+  game.wrap_axes(false, false).spawn_protection(Duration::ZERO);
+  game.tick(Some(b'p')).unwrap();
+
+  assert!(deaths_while_steering_right(&mut game, 600) > 1);
+}
+
+#[test]
+fn eaten_food_leaves_a_short_lived_particle() {
+  let mut game = solo();
+  game.eat_fx(true);
+  game.tick(Some(b'p')).unwrap();
+  let len = game.snakes()[0].len();
+
+  for _ in 0..4000 {
+    if game.snakes()[0].len() > len {
+      break;
+    }
+    let head = *game.snakes()[0].head();
+    let target = game.food()[0].position;
+    game.tick(Some(if head.x != target.x { b'd' } else { b's' })).unwrap();
+  }
+  assert!(!game.particles().is_empty());
+
+  let mut frames = 1;
+  loop {
+    game.tick(None).unwrap();
+    if game.particles().is_empty() {
+      break;
+    }
+    frames += 1;
+  }
+  assert_eq!(frames, PARTICLE_FRAMES);
+}
+
+#[test]
+fn logo_is_centered_by_its_width() {
+  let logo_x = |logo: &str| {
+    let mut game = Game::headless(80, 30);
+    game.seed(1).logo(logo).controls(vec!["Custom control".to_string()]);
+    let frame = game.tick(None).unwrap().to_string();
+    assert!(frame.contains("Custom control"));
+    position_of(&frame, logo.lines().last().unwrap()).unwrap().0
+  };
+
+  let narrow = logo_x("ab");
+  let wide = logo_x("abcdefghij\nxy");
+  assert_eq!(narrow - wide, 4);
+}
+
+#[test]
+fn ramp_speeds_up_ai_each_interval() {
+  // 50ms frames level up every fourth frame, paused frames don't count
+  let mut game = Game::headless(80, 30);
+  game.seed(5).opponents(&[Strategy::Eat]).fps(20).ramp(true, Duration::from_millis(200), 3);
+  game.tick(None).unwrap();
+  game.tick(Some(b'p')).unwrap();
+  let interval = game.snakes()[1].move_interval();
+
+  for _ in 0..6 {
+    game.tick(None).unwrap();
+  }
+  assert_eq!(game.level(), 1);
+  game.tick(Some(b'p')).unwrap();
+  for _ in 0..10 {
+    game.tick(None).unwrap();
+  }
+  assert_eq!(game.level(), 1);
+  game.tick(Some(b'p')).unwrap();
+  assert_eq!(game.level(), 2);
+
+  assert_eq!(interval - game.snakes()[1].move_interval(), Duration::from_millis(6));
+  assert!(game.tick(None).unwrap().contains("LEVEL: 2"));
+}
+
+#[test]
+fn ramp_counts_frames_not_updates() {
+  // A second of 1ms loop iterations at 20fps is 20 frames, five levels at 200ms each
+  let mut game = Game::headless(80, 30);
+  game.seed(5).opponents(&[Strategy::Eat]).fps(20).ramp(true, Duration::from_millis(200), 3);
+  game.tick(Some(b'p')).unwrap();
+  let frames = (0..1000).filter(|_| game.advance(Duration::from_millis(1), None).unwrap().is_some()).count();
+  assert_eq!(frames, 20);
+  assert_eq!(game.level(), 5);
+}
+
+#[test]
+fn occupied_cells_cover_bodies_and_food() {
+  let mut game = Game::headless(80, 30);
+  game.seed(9).opponents(&[Strategy::Eat]);
+  game.tick(None).unwrap();
+
+  let cells: Vec<_> = game.occupied_cells().collect();
+  assert_eq!(cells.len(), 8 * 2 + 12);
+  for snake in game.snakes() {
+    assert!(cells.contains(snake.head()));
+  }
+  for food in game.food() {
+    assert!(cells.contains(&food.position));
+  }
+}
+
+#[test]
+fn wall_policies() {
+  let mut game = solo();
+  game.wrap_axes(false, false).wall_policy(WallPolicy::Kill);
+  game.tick(Some(b'p')).unwrap();
+  assert!(deaths_while_steering_right(&mut game, 600) > 0);
+
+  let mut game = solo();
+  game.wrap_axes(false, false).wall_policy(WallPolicy::Bounce);
+  game.tick(Some(b'p')).unwrap();
+  assert_eq!(deaths_while_steering_right(&mut game, 600), 0);
+  assert_eq!(game.snakes()[0].len(), 8);
+
+  let mut game = solo();
+  game.wrap_axes(false, false).wall_policy(WallPolicy::Penalty(2));
+  game.tick(Some(b'p')).unwrap();
+  let mut lengths = vec![game.snakes()[0].len()];
+  for _ in 0..600 {
+    assert_eq!(deaths_while_steering_right(&mut game, 1), 0);
+    lengths.push(game.snakes()[0].len());
+  }
+  lengths.dedup();
+  assert_eq!(lengths, [8, 6, 4, 3]);
+}
+
+#[test]
+fn new_records_are_saved() {
+  let path = std::env::temp_dir().join(format!("snakers_records_{}", std::process::id()));
+  std::fs::write(&path, "Player 3\nEat 500\n").unwrap();
+
+  let mut game = solo();
+  game.records_file(&path).opponents(&[Strategy::Eat]);
+  game.tick(Some(b'p')).unwrap();
+  game.tick(Some(b'w')).unwrap();
+  game.save_records().unwrap();
+  let saved = std::fs::read_to_string(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+
+  assert_eq!(game.records().get("Player"), Some(8));
+  assert!(saved.contains("Player 8\n"));
+  assert!(saved.contains("Eat 500\n"));
+}
+
+const ROUTE: &[u8] = b"dddddsssssaaaawwwwdddddddddddsssssssssssssaaaaaaaaaaaaaaaaaaawwwwwwwwwwwwwwwwwwwwwwwwdddddddddddddddddddddddddddddddddddddddssssssssssssssssssssssssssssssssssssss";
+
+fn trace(game: &mut Game, ticks: usize) -> Vec<(u8, u8, usize)> {
+  game.tick(Some(b'p')).unwrap();
+  ROUTE
+    .iter()
+    .cycle()
+    .take(ticks)
+    .map(|&key| {
+      game.tick(Some(key)).unwrap();
+      let snake = &game.snakes()[0];
+      (snake.head().x, snake.head().y, snake.len())
+    })
+    .collect()
+}
+
+// Head and length every 24 steps, recorded from the ring buffer body before it became a VecDeque
+#[test]
+fn movement_and_growth_match_recorded_sequence() {
+  let mut game = Game::headless(80, 30);
+  game.seed(11).opponents(&[]).step_mode(true);
+  let sampled: Vec<_> = trace(&mut game, ROUTE.len() * 3).into_iter().step_by(24).collect();
+
+  assert_eq!(
+    sampled,
+    [
+      (24, 46, 8),
+      (31, 47, 8),
+      (28, 12, 8),
+      (16, 0, 8),
+      (28, 36, 8),
+      (12, 36, 8),
+      (15, 9, 8),
+      (20, 28, 8),
+      (27, 29, 8),
+      (14, 40, 9),
+      (8, 22, 11),
+      (26, 16, 11),
+      (7, 19, 11),
+      (7, 43, 11),
+      (9, 11, 11),
+      (19, 15, 11),
+      (0, 20, 11),
+      (0, 44, 11),
+      (24, 44, 11),
+      (39, 5, 11),
+      (39, 29, 12),
+    ]
+  );
+}
+
+// Recorded again once bouncing started heading away from the old tail instead of straight back
+#[test]
+fn shrinking_matches_recorded_sequence() {
+  let mut game = Game::headless(80, 30);
+  game.seed(11).opponents(&[]).step_mode(true).wrap_axes(false, false).wall_policy(WallPolicy::Penalty(1));
+  let heads = trace(&mut game, ROUTE.len() * 3);
+  let sampled: Vec<_> = heads[..319].iter().copied().step_by(24).collect();
+
+  assert_eq!(
+    sampled,
+    [
+      (24, 46, 8),
+      (30, 43, 6),
+      (27, 35, 5),
+      (15, 23, 5),
+      (27, 11, 5),
+      (24, 11, 5),
+      (21, 32, 5),
+      (26, 45, 4),
+      (33, 43, 3),
+      (20, 39, 4),
+      (14, 21, 5),
+      (32, 15, 5),
+      (23, 18, 4),
+      (23, 42, 4),
+    ]
+  );
+  // The ring buffer lost track of the head here, trimming the tail at index 0 sent it across the arena
+  assert_eq!(heads[317..321], [(23, 47, 4), (23, 45, 3), (23, 44, 3), (23, 43, 3)]);
+}
+
+#[test]
+fn legend_fits_within_the_arena() {
+  let controls: Vec<_> = (0..14).map(|i| format!("{} -> Control with a fairly long description", (b'a' + i) as char)).collect();
+  for legend in [Legend::SingleColumn, Legend::TwoColumns, Legend::Compact] {
+    let mut game = Game::headless(80, 30);
+    game.seed(1).legend(legend).controls(controls.clone());
+    let frame = game.tick(None).unwrap().to_string();
+    let (left, _) = position_of(&frame, "╔").unwrap();
+    let width = frame.split('╔').nth(1).unwrap().chars().take_while(|&c| c == '═').count() as u16;
+
+    let entries: Vec<_> = frame
+      .split("\x1b[")
+      .filter_map(|seq| {
+        let (_, text) = seq.split_once('H')?;
+        text.contains("Control").then(|| (cursor_moves(&format!("\x1b[{seq}"))[0].0, text.chars().count() as u16))
+      })
+      .collect();
+    assert!(!entries.is_empty(), "{legend:?}");
+    for (x, len) in entries {
+      assert!(x > left && x + len <= left + width + 1, "{legend:?} entry at {x} with {len} chars");
+    }
+  }
+}
+
+#[test]
+fn teams_are_assigned_on_spawn() {
+  let teams = |teams| {
+    let mut game = Game::headless(80, 30);
+    game.seed(2).opponents(&[Strategy::Eat, Strategy::Kill, Strategy::Eat]).teams(teams);
+    game.tick(None).unwrap();
+    game.snakes().iter().map(|snake| snake.team).collect::<Vec<_>>()
+  };
+
+  assert_eq!(teams(Teams::Solo), [0, 1, 2, 3]);
+  assert_eq!(teams(Teams::ByStrategy), [0, 1, 2, 1]);
+  assert_eq!(teams(Teams::Assigned(vec![4, 4])), [4, 4, 253, 252]);
+}
+
+#[test]
+fn god_mode_survives_self_collision() {
+  let circling_deaths = |god_mode| {
+    let mut game = solo();
+    game.spawn_protection(Duration::ZERO).god_mode(god_mode);
+    game.tick(Some(b'p')).unwrap();
+    let mut deaths = 0;
+    for &key in b"dsaw".repeat(10).iter() {
+      game.tick(Some(key)).unwrap();
+      deaths += !game.snakes()[0].is_alive() as usize;
+    }
+    deaths
+  };
+
+  assert!(circling_deaths(false) > 0);
+  assert_eq!(circling_deaths(true), 0);
+}
+
+#[test]
+fn god_mode_survives_walls() {
+  let wall_deaths = |god_mode| {
+    let mut game = solo();
+    game.arena_size(12, 12).wrap_axes(false, false).wall_policy(WallPolicy::Kill).spawn_protection(Duration::ZERO).god_mode(god_mode);
+    game.tick(Some(b'p')).unwrap();
+    (0..30)
+      .filter(|_| {
+        game.tick(Some(b'd')).unwrap();
+        !game.snakes()[0].is_alive()
+      })
+      .count()
+  };
+
+  assert!(wall_deaths(false) > 0);
+  assert_eq!(wall_deaths(true), 0);
+}
+
+#[test]
+fn centered_arena_and_scoreboard_share_the_margins() {
+  for placement in [Placement::Right, Placement::Left, Placement::Below, Placement::Hidden] {
+    let mut game = Game::headless(100, 40);
+    game.seed(4).scoreboard(placement);
+    game.tick(None).unwrap();
+    let frame = game.center_arena().tick(None).unwrap().to_string();
+
+    let (left, top) = position_of(&frame, "╔").unwrap();
+    let width = frame.split('╔').nth(1).unwrap().chars().take_while(|&c| c == '═').count() as u16 + 2;
+    let height = frame.matches('║').count() as u16 / 2 + 2;
+    let (mut start, mut end, mut bottom) = (left, left + width - 1, top + height - 1);
+    for snake in game.snakes().iter().filter(|_| placement != Placement::Hidden) {
+      let entry = format!("{}: {}", snake.name, snake.score());
+      let (x, y) = cursor_moves(&frame[..frame.find(&entry).unwrap()]).pop().unwrap();
+      (start, end, bottom) = (start.min(x), end.max(x + entry.chars().count() as u16 - 1), bottom.max(y));
+    }
+
+    assert!((start - 1).abs_diff(100 - end) <= 1, "{placement:?} spans {start}..={end}");
+    assert!((top - 1).abs_diff(40 - bottom) <= 1, "{placement:?} spans rows {top}..={bottom}");
+  }
+}
+
+#[test]
+fn food_relocates_after_its_lifetime() {
+  let moved_food = |lifetime| {
+    let mut game = solo();
+    game.food_lifetime(lifetime);
+    game.tick(Some(b'p')).unwrap();
+    let before: Vec<_> = game.food().iter().map(|food| food.position).collect();
+    std::thread::sleep(Duration::from_millis(30));
+    game.tick(None).unwrap();
+    game.food().iter().zip(&before).filter(|(food, before)| food.position != **before).count()
+  };
+
+  assert_eq!(moved_food(None), 0);
+  assert!(moved_food(Some(Duration::from_millis(20))) >= 10);
+}
+
+#[test]
+fn snakes_blending_into_the_background_show_a_warning() {
+  let mut game = Game::headless(80, 30);
+  game.seed(7).opponents(&[Strategy::Eat]);
+  assert!(!game.tick(None).unwrap().contains("LOW CONTRAST"));
+
+  // Pale snakes on a white terminal are hard to see
+  game.background(231);
+  let cells = drawn_cells(game.tick(None).unwrap());
+  // Right aligned with the arena's top right corner, two rows above it
+  let x = 20 + 40 - 12 + 2;
+  assert!("LOW CONTRAST".chars().enumerate().all(|(i, c)| cells.contains(&((x + i as u16, 1), c))));
+}
+
+#[test]
+fn ascii_charset_emits_only_ascii() {
+  let mut game = Game::headless(80, 30);
+  game.seed(6).charset(CharSet::Ascii).grid(Some(4)).eat_fx(true).smooth(true);
+  assert!(game.tick(None).unwrap().is_ascii());
+
+  game.tick(Some(b'p')).unwrap();
+  game.tick(Some(b'f')).unwrap();
+  for _ in 0..200 {
+    let frame = game.tick(None).unwrap();
+    assert!(frame.is_ascii(), "{frame}");
+  }
+}
+
+#[test]
+fn food_has_its_own_ascii_glyph_and_color() {
+  let effects = [Effect::None, Effect::Speed, Effect::Nourish, Effect::Cannibal, Effect::Magnet, Effect::Split, Effect::Swap];
+  let glyphs: HashSet<char> = effects.iter().map(|effect| CharSet::Ascii.glyph(effect.glyph())).collect();
+  assert_eq!(glyphs.len(), effects.len());
+  assert!(!glyphs.contains(&'?'));
+
+  let strategies = [Strategy::Player, Strategy::Speed, Strategy::Score, Strategy::Eat, Strategy::Kill, Strategy::Cannibal, Strategy::Adaptive, Strategy::Pack];
+  let colors: HashSet<u8> = effects.iter().map(|effect| effect.color()).collect();
+  assert_eq!(colors.len(), effects.len());
+  assert!(strategies.iter().all(|strat| !colors.contains(&strat.color())));
+}
+
+#[test]
+fn viewport_scrolls_over_a_large_arena() {
+  // 60 columns minus the arena position and scoreboard padding leave 29 columns, 19 rows hold 38 logical rows
+  const VIEW: (u8, u8) = (29, 38);
+  const FOOD: [char; 4] = ['󰉛', '', '󱩡', ''];
+  let mut game = Game::headless(60, 24);
+  game.seed(7).opponents(&[]).step_mode(true).viewport(true).arena_size(120, 60);
+  game.wrap_axes(false, false).wall_policy(WallPolicy::Bounce);
+  game.tick(Some(b'p')).unwrap();
+
+  let (mut left_edge, mut right_edge) = (false, false);
+  for key in b"a".repeat(300).into_iter().chain(b"d".repeat(300)) {
+    let frame = game.tick(Some(key)).unwrap().to_string();
+    let camera = game.camera();
+    let head = *game.snakes()[0].head();
+    assert!(camera.x + VIEW.0 <= 120 && camera.y + VIEW.1 <= 120, "camera {camera:?} past the arena");
+    if head.x < VIEW.0 / 2 {
+      assert_eq!(camera.x, 0);
+      left_edge = true;
+    } else if head.x >= 120 - VIEW.0 / 2 {
+      assert_eq!(camera.x, 120 - VIEW.0);
+      right_edge = true;
+    }
+
+    let visible = game
+      .food()
+      .iter()
+      .filter(|food| (camera.x..camera.x + VIEW.0).contains(&food.position.x) && (camera.y..camera.y + VIEW.1).contains(&food.position.y))
+      .count();
+    assert_eq!(frame.chars().filter(|c| FOOD.contains(c)).count(), visible);
+    for (x, y) in cursor_moves(&frame) {
+      assert!((1..=60).contains(&x) && (1..=24).contains(&y), "moved to {x}:{y}");
+    }
+  }
+  assert!(left_edge && right_edge);
+}
+
+#[test]
+fn camera_clamps_at_arena_edges() {
+  const VIEW: (u8, u8) = (29, 38);
+  let mut game = Game::headless(60, 24);
+  game.seed(7).opponents(&[]).step_mode(true).viewport(true).arena_size(120, 60);
+  game.wrap_axes(false, false).wall_policy(WallPolicy::Bounce);
+  game.tick(Some(b'p')).unwrap();
+
+  let (mut top_edge, mut bottom_edge) = (false, false);
+  for key in b"w".repeat(150).into_iter().chain(b"s".repeat(150)) {
+    let frame = game.tick(Some(key)).unwrap().to_string();
+    let camera = game.camera();
+    let head = *game.snakes()[0].head();
+    assert!(camera.y.is_multiple_of(2) && camera.y + VIEW.1 <= 120, "camera {camera:?} past the arena");
+    if head.y < VIEW.1 / 2 {
+      assert_eq!(camera.y, 0);
+      top_edge = true;
+    } else if head.y >= 120 - VIEW.1 / 2 {
+      assert_eq!(camera.y, 120 - VIEW.1);
+      bottom_edge = true;
+    }
+    for (x, y) in cursor_moves(&frame) {
+      assert!((1..=60).contains(&x) && (1..=24).contains(&y), "moved to {x}:{y}");
+    }
+  }
+  assert!(top_edge && bottom_edge);
+}
+
+#[test]
+fn step_mode_waits_for_input() {
+  let mut game = solo();
+  game.tick(Some(b'p')).unwrap();
+  let body = game.snakes()[0].body.clone();
+  for _ in 0..50 {
+    game.tick(None).unwrap();
+  }
+  assert_eq!(game.snakes()[0].body, body);
+
+  game.tick(Some(b'd')).unwrap();
+  assert_ne!(game.snakes()[0].body, body);
+}
+
+#[test]
+fn render_bounds_match_the_drawn_frame() {
+  for placement in [Placement::Right, Placement::Left, Placement::Below] {
+    let mut game = solo();
+    game.opponents(&[Strategy::Eat, Strategy::Kill]).scoreboard(placement).stats_pane(placement);
+    game.tick(Some(b'p')).unwrap();
+    let frame = game.tick(Some(b'd')).unwrap().to_string();
+
+    let cells: Vec<_> = drawn_cells(&frame).into_iter().map(|(cell, _)| cell).collect();
+    let (left, right) = (cells.iter().map(|c| c.0).min().unwrap(), cells.iter().map(|c| c.0).max().unwrap());
+    let (top, bottom) = (cells.iter().map(|c| c.1).min().unwrap(), cells.iter().map(|c| c.1).max().unwrap());
+    assert_eq!(game.render_bounds(), (right - left + 1, bottom - top + 1), "{placement:?}");
+  }
+}
+
+#[test]
+fn grid_dots_only_show_on_empty_cells() {
+  let mut game = solo();
+  game.opponents(&[Strategy::Eat, Strategy::Kill]).step_ai(true).grid(Some(2));
+  game.tick(Some(b'p')).unwrap();
+  for _ in 0..20 {
+    let screen: HashMap<_, _> = drawn_cells(game.tick(Some(b'd')).unwrap()).into_iter().collect();
+    let covered: HashSet<_> = (game.snakes().iter().flat_map(|snake| snake.body.iter()))
+      .chain(game.food().iter().map(|food| &food.position))
+      .map(|p| (21 + p.x as u16, 4 + (p.y >> 1) as u16))
+      .collect();
+
+    for x in (0..40).step_by(2) {
+      for y in (0..24).step_by(2) {
+        let cell = (21 + x, 4 + y);
+        assert_eq!(screen.get(&cell) == Some(&'·'), !covered.contains(&cell), "{cell:?}");
+      }
+    }
+  }
+}
+
+#[test]
+fn snakes_of_one_strategy_get_distinct_colors() {
+  let mut game = solo();
+  game.opponents(&[Strategy::Eat, Strategy::Eat, Strategy::Eat]).reset();
+  let colors: HashSet<u8> = game.snakes().iter().map(|snake| snake.color).collect();
+  assert_eq!(colors.len(), 4);
+}
+
+#[test]
+fn reaching_the_target_length_wins() {
+  let mut game = solo();
+  game.target_length(Some(9)).opponents(&[Strategy::Eat]).step_ai(true);
+  game.tick(Some(b'p')).unwrap();
+  assert!(game.tick(None).unwrap().contains("FIRST TO 9"));
+
+  for _ in 0..4000 {
+    if game.summary().winner.is_some() {
+      break;
+    }
+    let head = *game.snakes()[0].head();
+    let target = game.food()[0].position;
+    game.tick(Some(if head.x != target.x { b'd' } else { b's' })).unwrap();
+  }
+
+  let summary = game.summary().clone();
+  let winner = summary.winner.unwrap();
+  assert!(summary.lengths[winner] >= 9);
+  assert_eq!(summary.lengths.iter().max(), Some(&summary.lengths[winner]));
+  let frame = game.tick(Some(b'd')).unwrap().to_string();
+  assert!(frame.contains(&format!("WINNER: {}", game.snakes()[winner].name)));
+  assert_eq!(game.snakes().iter().map(|snake| snake.len()).collect::<Vec<_>>(), summary.lengths);
+}
+
+// Snake heads, food count and arena size seen on each frame
+type Seen = Vec<(Vec<Point>, usize, Point)>;
+
+struct Recorder(Rc<RefCell<Seen>>);
+
+impl Renderer for Recorder {
+  fn render(&mut self, view: &GameView, out: &mut Frame) -> fmt::Result {
+    let heads = view.snakes().iter().map(|snake| *snake.head()).collect();
+    self.0.borrow_mut().push((heads, view.food().len(), view.arena_size()));
+    write!(out, "frame {}", view.frame_count())
+  }
+}
+
+#[test]
+fn custom_renderer_receives_the_game_state() {
+  let seen = Rc::new(RefCell::new(Vec::new()));
+  let mut game = solo();
+  game.opponents(&[Strategy::Eat]).renderer(Box::new(Recorder(Rc::clone(&seen))));
+  game.tick(Some(b'p')).unwrap();
+  let frame = game.tick(Some(b'd')).unwrap().to_string();
+  assert!(frame.ends_with("frame 1"));
+  assert!(!frame.contains("SCORE"));
+
+  let seen = seen.borrow();
+  assert_eq!(seen.len(), 2);
+  let (heads, food, size) = &seen[1];
+  assert_eq!(*heads, game.snakes().iter().map(|snake| *snake.head()).collect::<Vec<_>>());
+  assert_eq!(*food, game.food().len());
+  assert_eq!(*size, Point::new(40, 24));
+}
+
+#[test]
+fn animated_resize_ends_exactly_at_the_target() {
+  let seen = Rc::new(RefCell::new(Vec::new()));
+  let mut game = solo();
+  game.animate_resize(true).renderer(Box::new(Recorder(Rc::clone(&seen))));
+  for key in [b'l', b'j', b'j'] {
+    game.tick(Some(key)).unwrap();
+  }
+  for _ in 0..10 {
+    game.tick(None).unwrap();
+  }
+
+  let sizes: Vec<Point> = seen.borrow().iter().map(|&(_, _, size)| size).collect();
+  assert_eq!(sizes.last(), Some(&Point::new(44, 16)));
+  assert!(sizes.iter().any(|size| size.y > 16 && size.y < 24), "{sizes:?}");
+  assert!(sizes.windows(2).all(|w| w[1].x >= w[0].x && w[1].y <= w[0].y && w[0].y - w[1].y <= 4));
+}
+
+#[test]
+fn dashing_into_a_wall_dies_at_the_wall() {
+  let mut game = solo();
+  game.dash(1, Duration::from_secs(60), 3).wrap_axes(false, false);
+  game.tick(Some(b'p')).unwrap();
+  game.tick(Some(b'd')).unwrap();
+  let start = *game.snakes()[0].head();
+  game.tick(Some(b' ')).unwrap();
+  game.tick(Some(b'd')).unwrap();
+  assert_eq!(*game.snakes()[0].head(), start + (3, 0));
+  assert_eq!(game.snakes()[0].dash_charges(), 0);
+
+  game.dash(1, Duration::from_secs(60), 100);
+  game.tick(Some(b' ')).unwrap();
+  game.tick(Some(b'd')).unwrap();
+  let player = &game.snakes()[0];
+  assert!(!player.is_alive());
+  assert_eq!(player.head().x, 39);
+  assert_eq!(player.head().y, start.y);
+}
+
+#[test]
+fn dashes_eat_food_they_pass_over() {
+  let mut game = solo();
+  game.dash(1, Duration::from_secs(60), 3).wrap_axes(false, false);
+  game.tick(Some(b'p')).unwrap();
+  game.tick(Some(b'd')).unwrap();
+  let start = *game.snakes()[0].head();
+  let (len, score) = (game.snakes()[0].len(), game.snakes()[0].score());
+  let passed = [start + (1, 0), start + (2, 0)];
+  for p in passed {
+    game.spawn_food(Effect::Nourish, p).unwrap();
+  }
+  game.tick(Some(b' ')).unwrap();
+  game.tick(Some(b'd')).unwrap();
+  assert_eq!(*game.snakes()[0].head(), start + (3, 0));
+  assert!(game.food().iter().all(|food| !passed.contains(&food.position)));
+  assert!(game.snakes()[0].score() >= score + 2);
+  assert!(game.snakes()[0].len() > len);
+}
+
+#[test]
+fn benchmark_setup_is_reproducible() {
+  let heads = |game: &Game| game.snakes().iter().map(|snake| *snake.head()).collect::<Vec<_>>();
+  let (mut a, mut b) = (Game::benchmark_setup(3), Game::benchmark_setup(3));
+  assert_eq!((a.snakes().len(), a.food().len()), (8, 12));
+  for i in 0..200 {
+    let key = if i % 2 == 0 { b'd' } else { b's' };
+    a.tick(Some(key)).unwrap();
+    b.tick(Some(key)).unwrap();
+  }
+  assert_eq!(heads(&a), heads(&b));
+  assert!(a.snakes().iter().all(|snake| snake.head().x < 120 && snake.head().y < 120));
+}
+
+#[test]
+fn tron_trails_grow_every_step_and_stay_lethal() {
+  let route = |tron: bool| {
+    let mut game = solo();
+    game.tron(tron);
+    game.tick(Some(b'p')).unwrap();
+    let mut lengths = Vec::new();
+    // Run right, then double back one row below and cut up into the trail left at the start
+    for key in std::iter::repeat_n(b'd', 10).chain([b's']).chain(std::iter::repeat_n(b'a', 9)).chain([b'w']) {
+      game.tick(Some(key)).unwrap();
+      lengths.push(game.snakes()[0].len());
+    }
+    (game, lengths)
+  };
+
+  let (game, lengths) = route(true);
+  assert_eq!(lengths[..20], (9..29).collect::<Vec<_>>());
+  assert!(!game.snakes()[0].is_alive());
+  assert_eq!(game.summary().winner, None);
+  assert_eq!(game.summary().lengths, [28]);
+
+  let (game, lengths) = route(false);
+  assert!(lengths.iter().all(|&len| len == 8));
+  assert!(game.snakes()[0].is_alive());
+}
+
+#[test]
+fn self_collision_policies() {
+  let bite = |policy: SelfCollision| {
+    let mut game = solo();
+    game.self_collision(policy);
+    game.tick(Some(b'p')).unwrap();
+    // Unfold the spawn stack, then curl back into the fourth segment
+    for key in std::iter::repeat_n(b'd', 8).chain([b's', b'a', b'w']) {
+      game.tick(Some(key)).unwrap();
+    }
+    let player = &game.snakes()[0];
+    (player.is_alive(), player.len())
+  };
+
+  assert!(!bite(SelfCollision::Lethal).0);
+  assert_eq!(bite(SelfCollision::Ignore), (true, 8));
+  assert_eq!(bite(SelfCollision::Truncate), (true, 4));
+}
+
+#[test]
+fn frame_count_follows_rendered_frames() {
+  let mut game = solo();
+  assert_eq!(game.frame_count(), 0);
+  for _ in 0..25 {
+    game.tick(None).unwrap();
+  }
+  assert_eq!(game.frame_count(), 25);
+
+  // The round clock restarts on reset, so it reads between stopwatches started just before and after it
+  let before = Instant::now();
+  game.reset();
+  let after = Instant::now();
+  assert_eq!(game.frame_count(), 0);
+  for _ in 0..25 {
+    game.tick(None).unwrap();
+  }
+  let since_after = after.elapsed();
+  let elapsed = game.elapsed();
+  assert!(elapsed >= since_after && elapsed <= before.elapsed());
+}
+
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Captured {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0.lock().unwrap().extend_from_slice(buf);
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+struct Exploding;
+
+impl Renderer for Exploding {
+  fn render(&mut self, _: &GameView, _: &mut Frame) -> fmt::Result {
+    panic!("renderer blew up");
+  }
+}
+
+#[test]
+fn panics_restore_the_terminal() {
+  let captured = Captured::default();
+  let restore = RestoreOnPanic::install(captured.clone(), "\x1b[0m\x1b[?25h");
+  let mut game = solo();
+  game.renderer(Box::new(Exploding));
+  assert!(panic::catch_unwind(AssertUnwindSafe(|| game.tick(None).map(|_| ()))).is_err());
+  drop(restore);
+
+  let written = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+  assert!(written.starts_with("\x1b[0m\x1b[?25h"));
+  // Once dropped the hook no longer writes anything
+  let len = written.len();
+  assert!(panic::catch_unwind(AssertUnwindSafe(|| game.tick(None).map(|_| ()))).is_err());
+  assert_eq!(captured.0.lock().unwrap().len(), len);
+}
+
+#[test]
+fn each_player_steers_their_own_snake() {
+  let mut game = solo();
+  game.players(3).opponents(&[Strategy::Eat]);
+  game.tick(None).unwrap();
+  let names: Vec<_> = game.snakes().iter().map(|snake| snake.name).collect();
+  assert_eq!(names[..3], ["P1", "P2", "P3"]);
+  assert!(game.snakes()[..3].iter().all(|snake| snake.is_human()) && !game.snakes()[3].is_human());
+
+  let before: Vec<_> = game.snakes().iter().map(|snake| snake.direction()).collect();
+  let (key, dir) = match before[2] {
+    Direction::Up | Direction::Down => (b'J', Direction::Left),
+    Direction::Left | Direction::Right => (b'I', Direction::Up),
+  };
+  game.tick(Some(key)).unwrap();
+  let after: Vec<_> = game.snakes().iter().map(|snake| snake.direction()).collect();
+  assert_eq!(after[2], dir);
+  assert_eq!([after[0], after[1], after[3]], [before[0], before[1], before[3]]);
+
+  // Player 4's keys do nothing with three players
+  game.tick(Some(b'T')).unwrap();
+  assert_eq!(game.snakes().iter().map(|snake| snake.direction()).collect::<Vec<_>>(), after);
+}
+
+#[test]
+fn player_keys_must_not_collide() {
+  let mut game = solo();
+  assert!(matches!(game.player_keys(&[*b"wasd", *b"wxyz"]), Err(GameError::KeyConflict(b'w'))));
+  assert!(matches!(game.player_keys(&[*b"wasp"]), Err(GameError::KeyConflict(b'p'))));
+  assert!(game.player_keys(&[*b"WASD", *b"wasd"]).is_ok());
+}
+
+#[test]
+fn stats_pane_stays_on_screen_and_matches_the_game() {
+  for placement in [Placement::Right, Placement::Left, Placement::Below] {
+    let mut game = Game::headless(120, 40);
+    game.seed(5).opponents(&[Strategy::Eat, Strategy::Kill]).scoreboard(placement).stats_pane(placement);
+    let frame = game.tick(None).unwrap().to_string();
+
+    let longest = game.snakes().iter().max_by_key(|snake| snake.len()).unwrap();
+    let lines = [
+      "FOOD: 12".to_string(),
+      "ALIVE: 3/3".to_string(),
+      format!("LONGEST: {} {}", longest.name, longest.len()),
+      "FRAME: 0".to_string(),
+    ];
+    let scoreboard: Vec<_> = (game.snakes().iter())
+      .map(|snake| *cursor_moves(&frame[..frame.find(&format!("{}: ", snake.name)).unwrap()]).last().unwrap())
+      .collect();
+    for ln in &lines {
+      let (x, y) = position_of(&frame, ln).unwrap_or_else(|| panic!("{placement:?} is missing {ln:?}"));
+      assert!(x >= 1 && x + ln.len() as u16 - 1 <= 120 && (1..=40).contains(&y), "{placement:?} {ln:?} at {x}:{y}");
+      // Entries are at most a dozen columns wide, the pane starts past them or on a row of its own
+      for &(sx, sy) in &scoreboard {
+        assert!(sy != y || sx + 12 < x || x + ln.len() as u16 <= sx, "{placement:?} {ln:?} at {x}:{y} overlaps {sx}:{sy}");
+      }
+    }
+    assert!(frame.contains("FASTEST: "));
+  }
+}
+
+#[test]
+fn steering_while_paused_aims_the_first_move() {
+  let mut game = Game::headless(80, 30);
+  game.seed(9).opponents(&[]).tick_based_speed(true);
+  game.tick(None).unwrap();
+  let (key, offset) = match game.snakes()[0].direction() {
+    Direction::Up => (b'a', (-1, 0)),
+    _ => (b'w', (0, -1)),
+  };
+  let start = *game.snakes()[0].head();
+  game.tick(Some(key)).unwrap();
+  game.tick(Some(b'p')).unwrap();
+  while *game.snakes()[0].head() == start {
+    game.tick(None).unwrap();
+  }
+  assert_eq!(*game.snakes()[0].head(), start + offset);
+}
+
+#[test]
+fn quick_turns_are_queued_instead_of_folding_back() {
+  let mut game = solo();
+  game.tick(Some(b'p')).unwrap();
+  for key in [b'd', b'd', b'd'] {
+    game.tick(Some(key)).unwrap();
+  }
+  game.step_mode(false).tick_based_speed(true);
+  let start = *game.snakes()[0].head();
+  // Up then left before the next move would reverse into the neck if left replaced up
+  game.tick(Some(b'w')).unwrap();
+  game.tick(Some(b'a')).unwrap();
+  let mut heads = Vec::new();
+  while heads.len() < 2 {
+    game.tick(None).unwrap();
+    let head = *game.snakes()[0].head();
+    if heads.last().copied().unwrap_or(start) != head {
+      heads.push(head);
+    }
+  }
+  assert!(game.snakes()[0].is_alive());
+  assert_eq!(heads, [start + (0, -1), start + (-1, -1)]);
+}
+
+#[test]
+fn border_inset_keeps_snakes_off_the_frame() {
+  let mut game = solo();
+  game.border_inset(2).wrap_axes(false, false);
+  let frame = game.tick(Some(b'p')).unwrap().to_string();
+  let (left, top) = position_of(&frame, "╔").unwrap();
+  let top_edge = &frame[frame.find('╔').unwrap()..frame.find('╗').unwrap()];
+  let right = left + top_edge.chars().count() as u16;
+  assert_eq!(right - left - 1, 40);
+  assert!(frame.contains('░'));
+
+  let mut frame = frame;
+  let mut edge = None;
+  while game.snakes()[0].is_alive() {
+    edge = Some((*game.snakes()[0].head(), frame));
+    frame = game.tick(Some(b'd')).unwrap().to_string();
+  }
+  let (head, frame) = edge.unwrap();
+  assert_eq!(head.x, 35);
+  let cell = (left + 2 + 1 + head.x as u16, top + 2 + 1 + (head.y as u16 >> 1));
+  assert!(cell.0 < right - 2);
+  assert!(cursor_moves(&frame).contains(&cell));
+}
+
+#[test]
+fn name_tags_float_above_heads() {
+  let mut game = solo();
+  game.name_tags(true);
+  let frame = game.tick(Some(b'p')).unwrap().to_string();
+  let player = &game.snakes()[0];
+  let tag = format!("\x1b[38;5;{}m{}", player.color, player.name);
+  let head = Point::new(20 + player.head().x + 1, ((player.head().y + 2) >> 1) + 3);
+  assert_eq!(position_of(&frame, &tag), Some((head.x as u16 - 1, head.y as u16 - 1)));
+
+  while game.snakes()[0].head().x != 39 {
+    game.tick(Some(b'd')).unwrap();
+  }
+  let frame = game.tick(None).unwrap().to_string();
+  let player = &game.snakes()[0];
+  assert_eq!(player.head().x, 39);
+  let row = ((player.head().y + 2) >> 1) + 3;
+  assert_eq!(position_of(&frame, &tag), Some((21 + 40 - 3, row as u16 - 1)));
+}
+
+#[test]
+fn slow_ai_keeps_its_direction_between_decisions() {
+  let mut game = solo();
+  game.opponents(&[Strategy::Eat]).step_ai(true).ai_reaction(20);
+  game.tick(Some(b'p')).unwrap();
+  game.tick(Some(b'w')).unwrap();
+  let dir = game.snakes()[1].direction();
+  for _ in 0..19 {
+    game.tick(Some(b'w')).unwrap();
+    assert_eq!(game.snakes()[1].direction(), dir);
+  }
+}
+
+#[test]
+fn results_export_final_standings() {
+  let path = std::env::temp_dir().join(format!("snakers_results_{}", std::process::id()));
+  let mut game = solo();
+  game.opponents(&[Strategy::Eat]).wrap_axes(false, false).export_results(&path, Format::Csv);
+  game.tick(Some(b'p')).unwrap();
+  while game.snakes()[0].is_alive() {
+    game.tick(Some(b'd')).unwrap();
+  }
+  game.save_results().unwrap();
+  let csv = std::fs::read_to_string(&path).unwrap();
+  game.export_results(&path, Format::Json).save_results().unwrap();
+  let json = std::fs::read_to_string(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+
+  let rows: Vec<Vec<&str>> = csv.lines().map(|ln| ln.split(',').collect()).collect();
+  assert_eq!(rows[0], ["name", "strategy", "color", "length", "kills", "deaths", "survived"]);
+  assert_eq!(rows.len(), 3);
+  for (row, snake) in rows[1..].iter().zip(game.snakes()) {
+    let color = snake.color.to_string();
+    let length = snake.len().to_string();
+    assert_eq!(row[..4], [snake.name, snake.strategy().name(), &color, &length]);
+    assert!(row[6].parse::<f32>().is_ok());
+  }
+  assert_eq!(rows[1][4..6], ["0", "1"]);
+  assert_eq!(rows[2][5], "0");
+
+  assert!(json.starts_with("[\n") && json.ends_with("]\n"));
+  assert_eq!(json.lines().filter(|ln| ln.contains(r#""name": "#)).count(), 2);
+  assert!(json.contains(r#""name": "You", "strategy": "Player""#));
+  assert!(json.contains(r#""deaths": 1"#));
+
+  game.export_results(std::env::temp_dir().join("snakers_missing").join("results"), Format::Csv);
+  assert!(game.save_results().is_err());
+}
+
+#[test]
+fn snakes_spawn_in_their_regions() {
+  let regions = [Rect::new(0, 0, 10, 12), Rect::new(28, 34, 12, 14)];
+  for seed in 0..20 {
+    let mut game = solo();
+    game.seed(seed).opponents(&[Strategy::Eat]).spawn_regions(regions.to_vec());
+    game.tick(None).unwrap();
+    for (snake, region) in game.snakes().iter().zip(&regions) {
+      assert!(snake.body.iter().all(|p| region.contains(p)), "{:?} outside {region:?}", snake.head());
+    }
+  }
+}
+
+#[test]
+fn expired_food_rots_then_respawns() {
+  // Without a lifetime food rots on the first unpaused tick and decays on the next one
+  let mut game = solo();
+  game.rot(true).food_lifetime(Some(Duration::ZERO));
+  game.tick(None).unwrap();
+  let fresh: Vec<_> = game.food().iter().map(|food| food.position).collect();
+  assert!(game.food().iter().all(|food| !food.is_rotten()));
+  let frame = game.tick(Some(b'p')).unwrap().to_string();
+
+  let rotten: Vec<_> = game.food().iter().filter(|food| food.is_rotten()).map(|food| food.position).collect();
+  assert!(rotten.len() >= 10);
+  assert!(rotten.iter().all(|p| fresh.contains(p)));
+  assert_eq!(frame.matches('✖').count(), rotten.len());
+
+  game.tick(None).unwrap();
+  assert!(game.food().iter().all(|food| !food.is_rotten() || !rotten.contains(&food.position)));
+  assert!(game.food().iter().filter(|food| food.is_rotten()).count() < rotten.len());
+}
+
+#[test]
+fn idle_players_wait_or_follow_the_autopilot() {
+  let run = |idle, keys: &[u8]| {
+    // Ticks last longer than a move so every tick moves a snake that isn't waiting
+    let mut game = Game::headless(80, 30);
+    game.seed(7).opponents(&[]).idle_behavior(idle).fps(10).tick_based_speed(true);
+    game.tick(Some(b'p')).unwrap();
+    let start = *game.snakes()[0].head();
+    for &key in keys {
+      game.tick((key != 0).then_some(key)).unwrap();
+    }
+    (start, game)
+  };
+
+  let (start, game) = run(Idle::Still, &[0; 3]);
+  assert_eq!(*game.snakes()[0].head(), start);
+  assert!(!game.snakes()[0].took_control());
+
+  let (start, mut game) = run(Idle::Still, &[0, b'w', 0, 0]);
+  assert_ne!(*game.snakes()[0].head(), start);
+  assert!(game.snakes()[0].took_control());
+
+  // A respawned player waits again until they steer
+  game.wrap_axes(false, false);
+  while game.snakes()[0].is_alive() {
+    game.tick(Some(b'd')).unwrap();
+  }
+  while !game.snakes()[0].is_alive() {
+    game.tick(None).unwrap();
+  }
+  assert!(!game.snakes()[0].took_control());
+  let respawned = *game.snakes()[0].head();
+  for _ in 0..3 {
+    game.tick(None).unwrap();
+  }
+  assert_eq!(*game.snakes()[0].head(), respawned);
+
+  let (start, game) = run(Idle::Autopilot(Strategy::Eat), &[0; 3]);
+  assert_ne!(*game.snakes()[0].head(), start);
+}
+
+#[test]
+fn tick_based_speed_ignores_wall_time() {
+  let moves = |pause: Duration| {
+    let mut game = Game::headless(80, 30);
+    game.seed(7).opponents(&[Strategy::Eat]).fps(50).tick_based_speed(true);
+    game.tick(Some(b'p')).unwrap();
+    let every = (game.snakes()[0].move_interval().as_millis() as f32 / 20.).round() as usize;
+    let mut head = *game.snakes()[0].head();
+    let moved: Vec<usize> = (1..=30)
+      .filter(|_| {
+        std::thread::sleep(pause);
+        game.tick(None).unwrap();
+        let moved = *game.snakes()[0].head() != head;
+        head = *game.snakes()[0].head();
+        moved
+      })
+      .collect();
+    (every, moved)
+  };
+
+  let (every, moved) = moves(Duration::ZERO);
+  assert_eq!(moved.len(), 30 / every);
+  assert!(moved.windows(2).all(|pair| pair[1] - pair[0] == every));
+  assert_eq!(moves(Duration::from_millis(5)).1, moved);
+}
+
+#[test]
+fn rate_limited_input_takes_one_turn_per_move() {
+  let spam = |limit| {
+    let mut game = Game::headless(80, 30);
+    // Short ticks so all the keys land before the first move
+    game.seed(7).opponents(&[]).input_rate_limit(limit).fps(1000).tick_based_speed(true);
+    game.tick(Some(b'w')).unwrap();
+    game.tick(Some(b'p')).unwrap();
+    for &key in b"adadads" {
+      game.tick(Some(key)).unwrap();
+    }
+    let mut dirs = Vec::new();
+    for _ in 0..2 {
+      let head = *game.snakes()[0].head();
+      while *game.snakes()[0].head() == head {
+        game.tick(None).unwrap();
+      }
+      dirs.push(Direction::between(&head, game.snakes()[0].head()).unwrap());
+    }
+    assert!(game.snakes()[0].is_alive());
+    dirs
+  };
+
+  assert_eq!(spam(false), [Direction::Left, Direction::Down]);
+  assert_eq!(spam(true), [Direction::Right, Direction::Right]);
+}
+
+#[test]
+fn death_banner_shows_after_the_player_dies() {
+  let mut game = solo();
+  game.wrap_axes(false, false).death_banner(Duration::from_millis(40));
+  let mut frame = game.tick(Some(b'p')).unwrap().to_string();
+  while game.snakes()[0].is_alive() {
+    assert!(!frame.contains("GAME OVER"));
+    frame = game.tick(Some(b'd')).unwrap().to_string();
+  }
+  assert_eq!(position_of(&frame, "GAME OVER"), Some((20 + (40 - 9) / 2 + 1, 3 + (24 - 2) / 2 + 1)));
+  assert!(frame.contains("You died! Respawning..."));
+
+  std::thread::sleep(Duration::from_millis(50));
+  assert!(!game.tick(None).unwrap().contains("GAME OVER"));
+
+  // A lone tron snake ends the game when it dies, without the end screen the banner still shows
+  let mut game = solo();
+  game.tron(true).wrap_axes(false, false).restart_prompt(false);
+  game.tick(Some(b'p')).unwrap();
+  while game.snakes()[0].is_alive() {
+    frame = game.tick(Some(b'd')).unwrap().to_string();
+  }
+  assert!(frame.contains("Eliminated.") && !frame.contains("GAME OVER"));
+}
+
+#[test]
+fn locked_aspect_keeps_the_arena_square() {
+  let rendered = |frame: &str| {
+    let top_edge = &frame[frame.find('╔').unwrap()..frame.find('╗').unwrap()];
+    (top_edge.chars().count() - 1, frame.matches('║').count() / 2)
+  };
+  let mut game = solo();
+  game.lock_aspect(Some(1.));
+  game.tick(Some(b'l')).unwrap();
+  let frame = game.tick(Some(b'l')).unwrap().to_string();
+  assert_eq!(rendered(&frame), (42, 21));
+
+  let frame = game.tick(Some(b'j')).unwrap().to_string();
+  assert_eq!(rendered(&frame), (40, 20));
+
+  game.animate_resize(true);
+  game.tick(Some(b'l')).unwrap();
+  let frame = (0..10).map(|_| game.tick(None).unwrap().to_string()).last().unwrap();
+  assert_eq!(rendered(&frame), (44, 22));
+}
+
+#[test]
+fn injected_food_can_be_eaten() {
+  let mut game = solo();
+  game.tick(Some(b'p')).unwrap();
+  let head = *game.snakes()[0].head();
+  assert!(matches!(game.spawn_food(Effect::Nourish, head), Err(GameError::Occupied(p)) if p == head));
+  assert!(matches!(game.spawn_food(Effect::Nourish, Point::new(40, 0)), Err(GameError::OutOfBounds(_))));
+  assert!(matches!(game.spawn_food(Effect::Nourish, Point::new(0, 48)), Err(GameError::OutOfBounds(_))));
+
+  let target = (0..40).map(|x| Point::new((head.x + 5 + x) % 40, (head.y + 7) % 48)).find(|&p| game.spawn_food(Effect::Nourish, p).is_ok()).unwrap();
+  assert_eq!(game.food().len(), 13);
+  assert_eq!(game.food().last().unwrap().position, target);
+  let len = game.snakes()[0].len();
+
+  for _ in 0..2000 {
+    let head = *game.snakes()[0].head();
+    if head == target {
+      break;
+    }
+    game.tick(Some(if head.x != target.x { b'd' } else { b's' })).unwrap();
+  }
+  game.tick(Some(b's')).unwrap();
+  assert!(game.snakes()[0].len() >= len + 2);
+  assert_ne!(game.food().last().unwrap().position, target);
+}
+
+#[test]
+fn losing_focus_pauses_until_focus_returns() {
+  let mut game = solo();
+  game.pause_on_blur(true);
+  let mut paused = |bytes: &[u8]| {
+    let mut frame = String::new();
+    for &b in bytes {
+      frame = game.tick(Some(b)).unwrap().to_string();
+    }
+    frame.contains("Press P to Play")
+  };
+  assert!(!paused(b"p"));
+
+  assert!(paused(b"\x1b[O"));
+  assert!(!paused(b"\x1b[I"));
+
+  // A pause the player asked for outlasts the terminal regaining focus
+  assert!(paused(b"p\x1b[O\x1b[I"));
+}
+
+#[test]
+fn tournament_totals_add_up() {
+  let mut tournament = Tournament::new(3);
+  tournament.seed(11).max_ticks(3000).setup(|game| {
+    game.opponents(&[Strategy::Eat, Strategy::Kill]);
+  });
+  let report = tournament.run().unwrap();
+
+  assert_eq!(report.games, 3);
+  let wins: usize = report.strategies.values().map(|s| s.wins).sum();
+  assert_eq!(wins + report.draws, 3);
+  assert_eq!(report.strategies.values().map(|s| s.entries).collect::<Vec<_>>(), [3, 3, 3]);
+  assert!(report.strategies.values().all(|s| s.win_rate() <= 1. && s.deaths <= s.entries));
+  let kills: usize = report.strategies.values().map(|s| s.kills).sum();
+  let deaths: usize = report.strategies.values().map(|s| s.deaths).sum();
+  assert!(kills <= deaths);
+  assert_eq!(tournament.run().unwrap(), report);
+}
+
+#[test]
+fn turn_cooldown_drops_steers_until_it_passes() {
+  let route = |cooldown| {
+    let mut game = solo();
+    game.turn_cooldown(cooldown);
+    game.tick(Some(b'p')).unwrap();
+    let mut dirs = Vec::new();
+    for &key in b"dsss" {
+      let head = *game.snakes()[0].head();
+      game.tick(Some(key)).unwrap();
+      dirs.push(Direction::between(&head, game.snakes()[0].head()).unwrap());
+    }
+    dirs
+  };
+
+  assert_eq!(route(0), [Direction::Right, Direction::Down, Direction::Down, Direction::Down]);
+  assert_eq!(route(2), [Direction::Right, Direction::Right, Direction::Down, Direction::Down]);
+}
+
+#[test]
+fn preferences_round_trip_and_override_defaults() {
+  let path = std::env::temp_dir().join(format!("snakers_prefs_{}", std::process::id()));
+  let _ = std::fs::remove_file(&path);
+  let mut game = Game::headless(80, 30);
+  game.with_preferences(&path);
+  let defaults = game.preferences();
+  assert_eq!((defaults.fps, defaults.arena_size), (Some(30), Some(Point::new(40, 24))));
+
+  game.fps(45).arena_size(30, 16).dash_key(b'x').charset(CharSet::Ascii);
+  game.player_keys(&[*b"yuio"]).unwrap();
+  game.save_preferences().unwrap();
+  let saved = game.preferences();
+  assert_eq!(Preferences::load(&path), saved);
+
+  let mut loaded = Game::headless(80, 30);
+  loaded.with_preferences(&path);
+  assert_eq!(loaded.preferences(), saved);
+  assert_eq!(saved.player_keys[0], *b"yuio");
+  assert_eq!((saved.fps, saved.arena_size, saved.dash_key), (Some(45), Some(Point::new(30, 16)), Some(b'x')));
+
+  // Broken lines fall back to the defaults, the rest still loads
+  std::fs::write(&path, "fps nope\narena 999 1\nkeys 1 2\ngarbage\ncharset ascii\n").unwrap();
+  let mut corrupt = Game::headless(80, 30);
+  corrupt.with_preferences(&path);
+  let preferences = corrupt.preferences();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!((preferences.fps, preferences.arena_size, preferences.charset), (defaults.fps, defaults.arena_size, Some(CharSet::Ascii)));
+  assert_eq!(preferences.player_keys, defaults.player_keys);
+}
+
+#[test]
+fn collision_grid_plays_out_like_scanning_bodies() {
+  let play = |grid: bool, tron: bool| {
+    let mut game = Game::benchmark_setup(5);
+    game.collision_grid(grid).tron(tron).reset();
+    let mut states = Vec::new();
+    for i in 0..300 {
+      game.tick(Some(if i % 2 == 0 { b'd' } else { b's' })).unwrap();
+      states.push(game.snakes().iter().map(|snake| (*snake.head(), snake.len(), snake.is_alive())).collect::<Vec<_>>());
+    }
+    states
+  };
+
+  assert_eq!(play(true, false), play(false, false));
+  assert_eq!(play(true, true), play(false, true));
+}
+
+#[test]
+fn demo_plays_itself_and_restarts_after_an_elimination() {
+  let mut game = Game::headless(80, 30);
+  game.seed(7).opponents(&[Strategy::Kill]).tron(true).tick_based_speed(true).demo(true);
+  // Demos start running and ignore the pause key
+  game.tick(Some(b'p')).unwrap();
+  let start = *game.snakes()[0].head();
+  assert!(!game.snakes()[0].is_human());
+
+  let (mut moved, mut restarted, mut frames) = (false, false, game.frame_count());
+  for _ in 0..5000 {
+    game.tick(None).unwrap();
+    moved |= *game.snakes()[0].head() != start;
+    if game.frame_count() < frames {
+      restarted = true;
+      assert!(game.snakes().iter().all(|snake| snake.is_alive() && snake.len() == 8));
+      break;
+    }
+    frames = game.frame_count();
+  }
+  assert!(moved && restarted);
+}
+
+#[test]
+fn invalid_config_is_rejected_or_clamped() {
+  let mut game = solo();
+  assert!(matches!(game.try_fps(0), Err(GameError::Config(ConfigError::Fps(0)))));
+  assert!(matches!(game.try_fps(5000), Err(GameError::Config(ConfigError::Fps(5000)))));
+  assert!(matches!(game.try_arena_size(4, 20), Err(GameError::Config(ConfigError::ArenaSize(p))) if p == Point::new(4, 20)));
+  assert!(matches!(game.try_arena_size(40, 200), Err(GameError::Config(ConfigError::ArenaSize(_)))));
+  assert!(matches!(game.try_border_inset(9), Err(GameError::Config(ConfigError::Inset(9)))));
+  assert!(matches!(game.try_lock_aspect(Some(f32::NAN)), Err(GameError::Config(ConfigError::AspectRatio(_)))));
+  assert!(matches!(game.try_lock_aspect(Some(-1.)), Err(GameError::Config(ConfigError::AspectRatio(_)))));
+  assert_eq!(GameError::from(ConfigError::Fps(0)).to_string(), "0 fps is outside 1..=1000");
+
+  game.try_fps(60).unwrap().try_arena_size(30, 16).unwrap().try_border_inset(2).unwrap().try_lock_aspect(None).unwrap();
+  game.tick(None).unwrap();
+  assert_eq!(game.snakes().len(), 1);
+
+  // The plain builders clamp instead, so a zero fps no longer divides by zero once the game runs
+  game.fps(0).arena_size(0, 0).border_inset(u8::MAX);
+  for key in *b"pdddd" {
+    game.tick(Some(key)).unwrap();
+  }
+}
+
+#[test]
+fn scaled_cells_repeat_glyphs_and_widen_the_border() {
+  let border = |game: &mut Game| {
+    let frame = game.tick(None).unwrap().to_string();
+    let edge = frame.lines().find(|ln| ln.contains('╔')).unwrap().chars().filter(|&c| c == '═').count();
+    (edge, frame.lines().filter(|ln| ln.contains('║')).count())
+  };
+  let mut game = solo();
+  game.arena_size(16, 8);
+  let plain = border(&mut game);
+
+  let mut game = solo();
+  game.arena_size(16, 8).scale(2, 1);
+  let frame = game.tick(None).unwrap().to_string();
+  assert_eq!(border(&mut game), (plain.0 * 2, plain.1));
+
+  let head = *game.snakes()[0].head();
+  let glyph = if head.y.is_multiple_of(2) { '▀' } else { '▄' };
+  let (x, y) = position_of(&frame, &glyph.to_string()).unwrap();
+  assert!(frame.contains(&format!("\x1b[{y};{x}H{glyph}{glyph}")));
+}
+
+#[test]
+fn tall_cells_stack_full_blocks_instead_of_striping_half_blocks() {
+  let rows = |height: u8| {
+    let mut game = solo();
+    game.arena_size(16, 8).scale(1, height);
+    let cells = drawn_cells(game.tick(Some(b'p')).unwrap());
+    let head = *game.snakes()[0].head();
+    let &((x, y), _) = cells.iter().find(|(_, c)| *c == '╔').unwrap();
+    let (x, top) = (x + 1 + head.x as u16, y + 1 + (head.y >> 1) as u16 * height as u16);
+    let rows: Vec<char> = (0..height as u16).map(|row| cells.iter().rev().find(|(p, _)| *p == (x, top + row)).map_or(' ', |&(_, c)| c)).collect();
+    (head.y.is_multiple_of(2), rows)
+  };
+
+  let (is_top, tall) = rows(2);
+  assert_eq!(tall, if is_top { ['█', ' '] } else { [' ', '█'] });
+  let (is_top, taller) = rows(3);
+  assert_eq!(taller, if is_top { ['█', '▀', ' '] } else { [' ', '▄', '█'] });
+}
+
+#[test]
+fn game_over_waits_for_restart_or_quit() {
+  let mut game = solo();
+  game.tron(true).wrap_axes(false, false);
+  game.tick(Some(b'p')).unwrap();
+  let len = game.snakes()[0].len();
+  let ended = (0..300).any(|_| {
+    game.tick(Some(b'd')).unwrap();
+    game.summary().finished
+  });
+  assert!(ended && game.is_running());
+
+  // Only R and Q do anything on the end screen
+  let frame = game.tick(Some(b'p')).unwrap().to_string();
+  assert!(frame.contains("Press R to restart, Q to quit.") && game.summary().finished);
+
+  game.tick(Some(b'r')).unwrap();
+  assert!(!game.summary().finished && game.is_running());
+  assert!(game.snakes()[0].is_alive());
+  assert_eq!(game.snakes()[0].len(), len);
+
+  game.tick(Some(b'q')).unwrap();
+  assert!(!game.is_running());
+}
+
+#[test]
+fn players_spawn_facing_the_start_direction() {
+  for (dir, key) in [(Direction::Up, b'w'), (Direction::Left, b'a'), (Direction::Down, b's'), (Direction::Right, b'd')] {
+    let mut game = solo();
+    game.player_start_dir(dir);
+    game.tick(Some(b'p')).unwrap();
+    let body = game.snakes()[0].body.clone();
+    assert_eq!(game.snakes()[0].direction(), dir);
+
+    game.tick(Some(key)).unwrap();
+    let snake = &game.snakes()[0];
+    assert!(snake.is_alive());
+    assert_eq!(*snake.head(), body[0] + dir.coords());
+    assert!(!body.contains(snake.head()));
+  }
+}
+
+struct Feast(Rc<Cell<usize>>);
+
+impl FoodEffect for Feast {
+  fn apply(&self, snake: &mut Snake) {
+    self.0.set(self.0.get() + 1);
+    snake.grow(5);
+  }
+
+  fn glyph(&self) -> char {
+    '@'
+  }
+
+  fn color(&self) -> u8 {
+    202
+  }
+}
+
+#[test]
+fn custom_food_effects_apply_when_eaten() {
+  let eaten = Rc::new(Cell::new(0));
+  let mut game = solo();
+  game.player_start_dir(Direction::Right).custom_food(Feast(Rc::clone(&eaten)), 2);
+  let frame = game.tick(Some(b'p')).unwrap().to_string();
+  assert_eq!(game.food().iter().filter(|food| food.effect() == Effect::Custom).count(), 2);
+  assert!(frame.contains('@'));
+
+  let head = *game.snakes()[0].head();
+  let len = game.snakes()[0].len();
+  game.spawn_custom_food(Feast(Rc::clone(&eaten)), head + Direction::Right.coords()).unwrap();
+  // Food is eaten on the move after the head reaches it
+  game.tick(Some(b'd')).unwrap();
+  game.tick(Some(b'd')).unwrap();
+  assert_eq!(eaten.get(), 1);
+  assert_eq!(game.snakes()[0].len(), len + 5);
+}
+
+#[test]
+fn handicapped_snakes_spawn_longer_with_a_laid_out_body() {
+  let mut game = Game::headless(80, 30);
+  game.seed(7).opponents(&[Strategy::Eat]).step_mode(true).player_start_dir(Direction::Right).handicap(1, 5).handicap(0, 6);
+  game.tick(None).unwrap();
+
+  let (player, rival) = (&game.snakes()[0], &game.snakes()[1]);
+  assert_eq!((player.len(), rival.len()), (14, 13));
+  for snake in [player, rival] {
+    let body: Vec<Point> = snake.body.iter().copied().collect();
+    assert!(body.windows(2).all(|w| w[0] == w[1] || Direction::between(&w[0], &w[1]).is_some()));
+    // The extra segments each take a cell of their own
+    let mut cells = body.clone();
+    cells.dedup();
+    assert_eq!(cells.len(), snake.len() - 7);
+  }
+  assert_eq!(player.body[8], *player.head() + Direction::Left.coords());
+}
+
+#[test]
+fn endless_arena_leaves_still_snakes_behind() {
+  let mut game = solo();
+  game.endless(1);
+  game.tick(Some(b'p')).unwrap();
+  let (start, head) = (game.scrolled(), game.snakes()[0].head().x);
+  let food = game.food()[0].position;
+
+  game.tick(None).unwrap();
+  assert_eq!(game.scrolled(), start + 1);
+  assert_eq!(game.snakes()[0].head().x, head - 1);
+  assert!(game.food()[0].position.x == food.x - 1 || food.x == 0);
+
+  // Without steering the player never moves and the edge catches up with it
+  let mut ticks = 0;
+  while game.snakes()[0].is_alive() && ticks <= 40 {
+    game.tick(None).unwrap();
+    ticks += 1;
+  }
+  assert!(!game.snakes()[0].is_alive());
+  assert_eq!(game.scrolled(), start + 1 + ticks);
+  assert_eq!(game.snakes()[0].stats().deaths, 1);
+}
+
+#[test]
+fn endless_scrolling_counts_frames_not_updates() {
+  // Half a second of 1ms loop iterations at 20fps is 10 frames, five columns at one every two frames
+  let mut game = solo();
+  game.fps(20).endless(2);
+  game.tick(Some(b'p')).unwrap();
+  let start = game.scrolled();
+  for _ in 0..500 {
+    game.advance(Duration::from_millis(1), None).unwrap();
+  }
+  assert_eq!(game.scrolled() - start, 5);
+}
+
+#[test]
+fn endless_food_comes_back_on_free_leading_cells() {
+  // Filling the trailing column brings back as much food as the leading column has cells
+  let mut game = solo();
+  game.arena_size(12, 8).endless(1);
+  game.tick(None).unwrap();
+  for y in 0..16 {
+    game.spawn_food(Effect::Nourish, Point::new(0, y)).ok();
+  }
+  let trailing = game.food().iter().filter(|food| food.position.x == 0).count();
+  game.tick(Some(b'p')).unwrap();
+
+  let leading: HashSet<(u8, u8)> = game.food().iter().map(|food| food.position).filter(|p| p.x == 11).map(|p| (p.x, p.y)).collect();
+  assert!(trailing > 8);
+  assert_eq!(leading.len(), trailing);
+}
+
+#[test]
+fn ending_endless_mode_restores_side_wrapping() {
+  let dies_at_the_side = |wrap_x: bool, rates: &[u8]| {
+    let mut game = solo();
+    game.arena_size(12, 12).wrap_axes(wrap_x, true).spawn_protection(Duration::ZERO).player_start_dir(Direction::Right);
+    for &rate in rates {
+      game.endless(rate);
+    }
+    game.tick(Some(b'p')).unwrap();
+    (0..30).any(|_| {
+      game.tick(Some(b'd')).unwrap();
+      !game.snakes()[0].is_alive()
+    })
+  };
+
+  assert!(!dies_at_the_side(true, &[3, 0]));
+  assert!(!dies_at_the_side(true, &[3, 5, 0]));
+  assert!(dies_at_the_side(false, &[3, 0]));
+}
+
+#[test]
+fn strategy_glyphs_replace_half_blocks() {
+  let mut game = Game::headless(80, 30);
+  game.seed(7).opponents(&[Strategy::Eat]).step_mode(true).strategy_glyphs(true);
+  let frame = game.tick(None).unwrap().to_string();
+  // The arena's top left corner sits at 20,3 on an 80x30 terminal
+  let screen = |p: &Point| (21 + p.x as u16, 4 + (p.y >> 1) as u16);
+
+  for (snake, glyph) in game.snakes().iter().zip(['@', 'o']) {
+    let (x, y) = screen(snake.head());
+    assert!(frame.contains(&format!("\x1b[{y};{x}H{glyph}")), "{} should be drawn with {glyph}", snake.name);
+  }
+}
@@ -0,0 +1,62 @@
+use snakers::{parse_sgr_mouse, Direction, Input, InputParser, Mouse, Point};
+
+fn feed(parser: &mut InputParser, bytes: &[u8]) -> Vec<Input> {
+  bytes.iter().filter_map(|b| parser.feed(*b)).collect()
+}
+
+#[test]
+fn parses_sgr_press_and_release() {
+  assert_eq!(
+    parse_sgr_mouse(b"0;12;7M"),
+    Some(Mouse {
+      button: 0,
+      position: Point::new(12, 7),
+      pressed: true,
+    })
+  );
+  assert_eq!(
+    parse_sgr_mouse(b"2;300;1m"),
+    Some(Mouse {
+      button: 2,
+      position: Point::new(255, 1),
+      pressed: false,
+    })
+  );
+}
+
+#[test]
+fn rejects_malformed_sgr() {
+  assert_eq!(parse_sgr_mouse(b"0;12M"), None);
+  assert_eq!(parse_sgr_mouse(b"0;12;7;1M"), None);
+  assert_eq!(parse_sgr_mouse(b"0;a;7M"), None);
+  assert_eq!(parse_sgr_mouse(b"0;12;7"), None);
+  assert_eq!(parse_sgr_mouse(b""), None);
+}
+
+#[test]
+fn parser_splits_keys_arrows_and_mouse() {
+  let mut parser = InputParser::new();
+  let inputs = feed(&mut parser, b"w\x1b[A\x1b[<0;4;5Ms");
+
+  assert_eq!(
+    inputs,
+    [
+      Input::Key(b'w'),
+      Input::Arrow(Direction::Up),
+      Input::Mouse(Mouse {
+        button: 0,
+        position: Point::new(4, 5),
+        pressed: true,
+      }),
+      Input::Key(b's'),
+    ]
+  );
+}
+
+#[test]
+fn parser_recognizes_focus_reports() {
+  let mut parser = InputParser::new();
+  let inputs = feed(&mut parser, b"\x1b[O\x1b[Iw");
+
+  assert_eq!(inputs, [Input::Focus(false), Input::Focus(true), Input::Key(b'w')]);
+}
@@ -0,0 +1,165 @@
+use snakers::{contrast, FlushPolicy, Frame, FrameClock, FrameTimes, FrameWriter, Point, RestoreOnExit, MIN_CONTRAST};
+use std::{fmt::Write, io, time::Duration};
+
+#[derive(Default)]
+struct Mock {
+  written: usize,
+  flushes: usize,
+}
+
+impl io::Write for &mut Mock {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.written += buf.len();
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.flushes += 1;
+    Ok(())
+  }
+}
+
+fn flushes(policy: FlushPolicy, frames: usize) -> usize {
+  let mut mock = Mock::default();
+  let mut out = FrameWriter::new(&mut mock, policy);
+  for _ in 0..frames {
+    out.write_frame("0123456789").unwrap();
+  }
+  drop(out);
+  assert_eq!(mock.written, frames * 10);
+  mock.flushes
+}
+
+#[test]
+fn per_frame_flushes_every_frame() {
+  assert_eq!(flushes(FlushPolicy::PerFrame, 5), 5 + 1);
+}
+
+#[test]
+fn buffer_full_flushes_once_the_threshold_is_reached() {
+  assert_eq!(flushes(FlushPolicy::BufferFull(25), 9), 3 + 1);
+}
+
+#[test]
+fn interval_only_flushes_on_drop_within_the_interval() {
+  assert_eq!(flushes(FlushPolicy::Interval(Duration::from_secs(60)), 20), 1);
+}
+
+#[test]
+fn restore_is_written_on_every_exit() {
+  let run = |fail: bool| -> (io::Result<()>, Vec<u8>) {
+    let mut written = Vec::new();
+    let result = (|| {
+      let _restore = RestoreOnExit::new(&mut written, "\x1b[?1000l\x1b[0m");
+      if fail {
+        return Err(io::Error::other("read failed"));
+      }
+      Ok(())
+    })();
+    (result, written)
+  };
+
+  for fail in [false, true] {
+    let (result, written) = run(fail);
+    assert_eq!(result.is_err(), fail);
+    assert_eq!(written, b"\x1b[?1000l\x1b[0m");
+  }
+}
+
+#[test]
+fn adjacent_cells_skip_cursor_moves() {
+  let mut f = Frame::default();
+  Point::new(3, 2).render('▀', &mut f).unwrap();
+  write!(f, "\x1b[38;5;84m").unwrap();
+  Point::new(4, 2).render('▄', &mut f).unwrap();
+  assert_eq!(f.as_str(), "\x1b[2;3H▀\x1b[38;5;84m▄");
+
+  // Text and wide glyphs leave the cursor somewhere unknown
+  write!(f, "text").unwrap();
+  Point::new(5, 2).render('󰉛', &mut f).unwrap();
+  Point::new(6, 2).render('▀', &mut f).unwrap();
+  assert!(f.ends_with("text\x1b[2;5H󰉛\x1b[2;6H▀"));
+}
+
+#[test]
+fn frame_clock_carries_leftover_time() {
+  let frame = Duration::from_millis(10);
+  let mut clock = FrameClock::new(frame, 5);
+  // Ticks of 4ms line up with frames at 12ms, 20ms, 32ms and 40ms
+  let due: Vec<u32> = (0..10).map(|_| clock.advance(Duration::from_millis(4))).collect();
+  assert_eq!(due, [0, 0, 1, 0, 1, 0, 0, 1, 0, 1]);
+  assert_eq!(clock.accumulator(), Duration::ZERO);
+
+  // A 13ms tick owes one frame and the 3ms left over shortens the wait for the next
+  assert_eq!(clock.advance(Duration::from_millis(13)), 1);
+  assert_eq!(clock.advance(Duration::from_millis(6)), 0);
+  assert_eq!(clock.advance(Duration::from_millis(1)), 1);
+}
+
+#[test]
+fn frame_clock_stays_bounded_after_a_slow_frame() {
+  let frame = Duration::from_micros(16_667);
+  let mut clock = FrameClock::new(frame, 5);
+  let mut now = Duration::ZERO;
+  let mut tick = |clock: &mut FrameClock, elapsed: Duration| {
+    now += elapsed;
+    let due = clock.advance(elapsed);
+    assert!(clock.accumulator() < frame, "{:?} owed at {now:?}", clock.accumulator());
+    due
+  };
+
+  for _ in 0..60 {
+    assert!(tick(&mut clock, Duration::from_millis(1)) <= 1);
+  }
+  assert_eq!(tick(&mut clock, Duration::from_secs(2)), 5);
+  // Once the stall is dropped the clock goes back to one frame at a time
+  let due: u32 = (0..100).map(|_| tick(&mut clock, Duration::from_millis(1))).sum();
+  assert!((5..=7).contains(&due), "{due} frames due");
+  for _ in 0..100 {
+    assert!(tick(&mut clock, Duration::from_millis(1)) <= 1);
+  }
+}
+
+#[test]
+fn frame_clock_drops_time_past_the_max_catchup() {
+  let mut clock = FrameClock::new(Duration::from_millis(10), 100);
+  clock.set_max_catchup(Duration::from_millis(45));
+  assert_eq!(clock.advance(Duration::from_secs(3600)), 4);
+  assert_eq!(clock.accumulator(), Duration::from_millis(5));
+  assert_eq!(clock.advance(Duration::from_millis(5)), 1);
+
+  clock.set_max_catchup(Duration::MAX);
+  assert_eq!(clock.advance(Duration::from_secs(3600)), 100);
+  assert_eq!(clock.accumulator(), Duration::ZERO);
+}
+
+#[test]
+fn contrast_flags_colors_that_blend_in() {
+  assert!((contrast(16, 231) - 21.).abs() < 0.01);
+  assert_eq!(contrast(196, 196), 1.);
+  assert_eq!(contrast(17, 16), contrast(16, 17));
+  // Dark blue on black and light gray on white are hard to see
+  assert!(contrast(17, 16) < MIN_CONTRAST);
+  assert!(contrast(253, 231) < MIN_CONTRAST);
+  assert!(contrast(226, 16) > MIN_CONTRAST);
+  assert!(contrast(41, 235) > MIN_CONTRAST);
+}
+
+#[test]
+fn sustained_slow_frames_flag_low_fps() {
+  let frame = Duration::from_micros(16_667);
+  let mut times = FrameTimes::new();
+  for _ in 0..59 {
+    times.push(25_000);
+  }
+  assert!(!times.is_slow(frame, 0.8));
+  times.push(25_000);
+  assert!(times.is_slow(frame, 0.8));
+
+  // A few hitches among on-time frames don't add up to a slow average
+  for i in 0..60 {
+    times.push(if i % 20 == 0 { 50_000 } else { 16_000 });
+  }
+  assert!(!times.is_slow(frame, 0.8));
+  assert!(times.is_slow(frame, 0.99));
+}